@@ -0,0 +1,72 @@
+//! Serves the last telegram read from a serial port or a file in the OpenMetrics format
+//!
+//! Usage: `cargo run --example openmetrics_server --features openmetrics -- /dev/ttyUSB0 [127.0.0.1:9898]`
+//!
+//! The metrics are available at `http://127.0.0.1:9898/metrics`. A regular file is read once, which is handy for testing
+//! with a recorded telegram dump.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::{env, io, thread};
+
+use dsmr_parse::{Telegram, TelegramReader};
+
+fn main() -> io::Result<()> {
+	let mut args = env::args().skip(1);
+	let Some(source) = args.next() else {
+		eprintln!("Usage: openmetrics_server <serial port or file> [listen address]");
+		return Ok(());
+	};
+	let addr = args.next().unwrap_or_else(|| "127.0.0.1:9898".to_string());
+
+	let src: Box<dyn Read + Send> = if Path::new(&source).is_file() {
+		Box::new(File::open(&source)?)
+	} else {
+		Box::new(serialport::new(&source, 115_200).open()?)
+	};
+	let last = Arc::new(Mutex::new(None::<Telegram>));
+	thread::spawn({
+		let last = Arc::clone(&last);
+		move || {
+			for telegram in TelegramReader::new(BufReader::new(src)) {
+				match telegram {
+					Ok(telegram) => *last.lock().unwrap() = Some(telegram),
+					Err(e) => eprintln!("Parse error: {e}"),
+				}
+			}
+		}
+	});
+
+	let listener = TcpListener::bind(&addr)?;
+	eprintln!("Serving metrics at http://{addr}/metrics");
+	for stream in listener.incoming() {
+		if let Err(e) = stream.and_then(|stream| respond(stream, &last)) {
+			eprintln!("Request error: {e}");
+		}
+	}
+	Ok(())
+}
+
+fn respond(mut stream: TcpStream, last: &Mutex<Option<Telegram>>) -> io::Result<()> {
+	let mut request_line = String::new();
+	BufReader::new(&stream).read_line(&mut request_line)?;
+	let (status, content_type, body) = if !request_line.starts_with("GET /metrics ") {
+		("404 Not Found", "text/plain", "Not found\n".to_string())
+	} else if let Some(telegram) = &*last.lock().unwrap() {
+		(
+			"200 OK",
+			"application/openmetrics-text; version=1.0.0; charset=utf-8",
+			telegram.to_openmetrics(),
+		)
+	} else {
+		("503 Service Unavailable", "text/plain", "No telegram read yet\n".to_string())
+	};
+	write!(
+		stream,
+		"HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+		body.len()
+	)
+}
@@ -0,0 +1,100 @@
+use core::future;
+use core::pin::Pin;
+use core::task::{Context, Poll, ready};
+
+use futures_core::Stream;
+use tokio::io::AsyncBufRead;
+
+use crate::{Error, ParseOptions, Telegram, TelegramDecoder};
+
+impl Telegram {
+	/// Try to read a single telegram from an [AsyncBufRead] source
+	///
+	/// Async counterpart of [Telegram::read_from], the source is not read past the end of the telegram, so the next one can be
+	/// read from it by passing it by `&mut`. Wrap the unbuffered sources with `tokio::io::BufReader`.
+	pub async fn read_from_async(src: impl AsyncBufRead + Unpin) -> Result<Option<Self>, Error> {
+		let mut stream = TelegramStream::new(src);
+		future::poll_fn(|cx| Pin::new(&mut stream).poll_next(cx)).await.transpose()
+	}
+}
+
+/// Continuous reader of telegrams from an [AsyncBufRead] source
+///
+/// Async counterpart of [TelegramReader](crate::TelegramReader) implementing [Stream], it has the same error recovery
+/// behavior. The source is never read past the end of the last yielded telegram, so it can be taken back with
+/// [TelegramStream::into_inner] without losing any data.
+///
+/// ```
+/// use dsmr_parse::TelegramStream;
+/// use futures_util::StreamExt;
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let data = b"/XMX5LGBBFG1009394887\r\n\r\n1-3:0.2.8(42)\r\n!0D7D\r\n";
+///
+/// let mut telegrams = TelegramStream::new(data.as_slice());
+/// while let Some(telegram) = telegrams.next().await {
+///     match telegram {
+///         Ok(telegram) => println!("Read telegram: {telegram:?}"),
+///         Err(e) => eprintln!("Parse error: {e}"),
+///     }
+/// }
+/// # }
+/// ```
+pub struct TelegramStream<R> {
+	inner: R,
+	decoder: TelegramDecoder,
+}
+
+impl<R: AsyncBufRead> TelegramStream<R> {
+	pub fn new(src: R) -> Self {
+		Self::with_options(src, ParseOptions::default())
+	}
+
+	pub fn with_options(src: R, options: ParseOptions) -> Self {
+		Self {
+			inner: src,
+			decoder: TelegramDecoder::with_options(options),
+		}
+	}
+
+	pub fn get_ref(&self) -> &R {
+		&self.inner
+	}
+
+	pub fn get_mut(&mut self) -> &mut R {
+		&mut self.inner
+	}
+
+	/// Number of bytes skipped before the header of the last telegram, assuming CRLF line terminators
+	pub fn skipped_bytes(&self) -> usize {
+		self.decoder.skipped_bytes()
+	}
+
+	/// Return the underlying source, the incomplete line that is read but not yet parsed is lost
+	pub fn into_inner(self) -> R {
+		self.inner
+	}
+}
+
+impl<R: AsyncBufRead + Unpin> Stream for TelegramStream<R> {
+	type Item = Result<Telegram, Error>;
+
+	fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		let this = self.get_mut();
+		loop {
+			let buf = match ready!(Pin::new(&mut this.inner).poll_fill_buf(cx)) {
+				Ok(buf) => buf,
+				Err(e) => return Poll::Ready(Some(Err(Error::Io(e)))),
+			};
+			if buf.is_empty() {
+				return Poll::Ready(this.decoder.finish());
+			}
+			let (consumed, res) = this.decoder.decode(buf);
+			Pin::new(&mut this.inner).consume(consumed);
+			if res.is_some() {
+				return Poll::Ready(res);
+			}
+		}
+	}
+}
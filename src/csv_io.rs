@@ -0,0 +1,611 @@
+use alloc::collections::BTreeSet;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use core::fmt::Display;
+use core::str::FromStr;
+use std::io;
+
+use crate::telegram::{OctetString, parse_octet_string};
+use crate::{Error, MbusDevice, MbusDeviceType, ObisCode, Telegram, Tst, UnitValue};
+
+/// Columns of the M-Bus devices, each prefixed with `mbus_<channel>_`
+const MBUS_COLUMNS: [&str; 6] = [
+	"device_type",
+	"equipment_id",
+	"valve_position",
+	"capture_date",
+	"capture_value",
+	"unit",
+];
+
+/// Objects written into the field columns
+const FIELD_OBIS: [ObisCode; 35] = [
+	ObisCode::VERSION,
+	ObisCode::ELECTRICITY_DATE,
+	ObisCode::ELECTRICITY_EQUIPMENT_ID,
+	ObisCode::ELECTRICITY_CONSUMED_TARIFF_1,
+	ObisCode::ELECTRICITY_CONSUMED_TARIFF_2,
+	ObisCode::ELECTRICITY_GENERATED_TARIFF_1,
+	ObisCode::ELECTRICITY_GENERATED_TARIFF_2,
+	ObisCode::CURRENT_TARIFF,
+	ObisCode::POWER,
+	ObisCode::RETURN_POWER,
+	ObisCode::POWER_FAILURE_COUNT,
+	ObisCode::LONG_POWER_FAILURE_COUNT,
+	ObisCode::VOLTAGE_SAG_L1_COUNT,
+	ObisCode::VOLTAGE_SAG_L2_COUNT,
+	ObisCode::VOLTAGE_SAG_L3_COUNT,
+	ObisCode::VOLTAGE_SWELL_L1_COUNT,
+	ObisCode::VOLTAGE_SWELL_L2_COUNT,
+	ObisCode::VOLTAGE_SWELL_L3_COUNT,
+	ObisCode::VOLTAGE_L1,
+	ObisCode::VOLTAGE_L2,
+	ObisCode::VOLTAGE_L3,
+	ObisCode::CURRENT_L1,
+	ObisCode::CURRENT_L2,
+	ObisCode::CURRENT_L3,
+	ObisCode::POWER_L1,
+	ObisCode::POWER_L2,
+	ObisCode::POWER_L3,
+	ObisCode::RETURN_POWER_L1,
+	ObisCode::RETURN_POWER_L2,
+	ObisCode::RETURN_POWER_L3,
+	ObisCode::EMUCS_VERSION,
+	ObisCode::CURRENT_AVERAGE_DEMAND,
+	ObisCode::BREAKER_STATE,
+	ObisCode::LIMITER_THRESHOLD,
+	ObisCode::FUSE_SUPERVISION_THRESHOLD,
+];
+
+/// Columns of the scalar [Telegram] fields
+const FIELD_COLUMNS: [&str; 37] = [
+	"ident",
+	"version",
+	"electricity_date",
+	"electricity_equipment_id",
+	"electricity_consumed_tariff_1",
+	"electricity_consumed_tariff_2",
+	"electricity_generated_tariff_1",
+	"electricity_generated_tariff_2",
+	"current_tariff",
+	"power",
+	"return_power",
+	"power_failure_count",
+	"long_power_failure_count",
+	"voltage_sag_l1_count",
+	"voltage_sag_l2_count",
+	"voltage_sag_l3_count",
+	"voltage_swell_l1_count",
+	"voltage_swell_l2_count",
+	"voltage_swell_l3_count",
+	"voltage_l1",
+	"voltage_l2",
+	"voltage_l3",
+	"current_l1",
+	"current_l2",
+	"current_l3",
+	"power_l1",
+	"power_l2",
+	"power_l3",
+	"return_power_l1",
+	"return_power_l2",
+	"return_power_l3",
+	"emucs_version",
+	"current_average_demand",
+	"breaker_state",
+	"limiter_threshold",
+	"fuse_supervision_threshold",
+	"crc_valid",
+];
+
+/// Column with the objects that don't have a column of their own, one object line per line of the cell
+const OBJECTS_COLUMN: &str = "objects";
+
+/// Writes the telegrams as CSV, one row per telegram
+///
+/// The header is written along with the first telegram and consists of:
+/// * the columns named after the scalar [Telegram] fields. The numbers have the unit in the column name, e.g. `power [kW]`,
+///   taken from the first telegram or the specification if it doesn't have the value. The values in a different unit are
+///   written with their own as in the telegram, e.g. `1500*W`, and `1500*` for the ones without the unit. The timestamps
+///   are in the RFC 3339 format with the CET/CEST offset derived from the DST flag and the tariff is as in the telegram
+///   (e.g. `0001`). The legacy (DSMR 2.2/3.0) timestamps don't have the DST flag, so they are written with `+01:00` even
+///   in summer, which still restores the same [Tst] but isn't the actual offset;
+/// * the `mbus_<channel>_<field>` columns for every M-Bus channel present in the first telegram;
+/// * the columns named after the OBIS codes of the remaining objects of the first telegram (including the text message,
+///   the power failure log and the e-MUCS demand objects) with their raw values in parentheses, e.g.
+///   `(1)(0-0:96.7.19)(221224180005W)(0000000240*s)`. This way an empty text message `()` is distinguished from a missing
+///   one;
+/// * the `objects` column with the lines of the objects of the later telegrams that have no column of their own, e.g. the
+///   M-Bus devices on the other channels.
+///
+/// The objects that failed to decode in the lenient mode (listed in [Telegram::field_errors]) are written into the OBIS code
+/// or `objects` columns as is, even if they belong to a field column.
+///
+/// [CsvReader] reads the telegrams back.
+///
+/// ```
+/// use dsmr_parse::{CsvReader, CsvWriter, Telegram};
+///
+/// let telegram = Telegram::read_from(include_bytes!("../tests/telegram.txt").as_slice()).unwrap().unwrap();
+/// let mut writer = CsvWriter::new(vec![]);
+/// writer.write(&telegram).unwrap();
+/// let csv = writer.into_inner().unwrap();
+/// let read = CsvReader::new(csv.as_slice()).unwrap().next().unwrap().unwrap();
+/// assert_eq!(telegram.electricity_consumed_tariff_1, read.electricity_consumed_tariff_1);
+/// assert_eq!(telegram.mbus_devices, read.mbus_devices);
+/// ```
+pub struct CsvWriter<W: io::Write> {
+	writer: csv::Writer<W>,
+	/// Columns decided by the first telegram
+	layout: Option<Layout>,
+}
+
+impl<W: io::Write> CsvWriter<W> {
+	pub fn new(dst: W) -> Self {
+		Self {
+			writer: csv::Writer::from_writer(dst),
+			layout: None,
+		}
+	}
+
+	/// Write the telegram as the next row, preceded by the header if it's the first one
+	pub fn write(&mut self, telegram: &Telegram) -> Result<(), Error> {
+		if self.layout.is_none() {
+			let layout = Layout::new(telegram);
+			self.writer.write_record(layout.header())?;
+			self.layout = Some(layout);
+		}
+		let row = self.layout.as_ref().expect("Just set").row(telegram);
+		self.writer.write_record(row)?;
+		Ok(())
+	}
+
+	/// Flush the buffered rows to the destination
+	pub fn flush(&mut self) -> Result<(), Error> {
+		self.writer.flush()?;
+		Ok(())
+	}
+
+	/// Flush the buffered rows and return the destination
+	pub fn into_inner(self) -> Result<W, Error> {
+		self.writer.into_inner().map_err(|e| Error::Io(e.into_error()))
+	}
+}
+
+/// Columns of the CSV besides the field ones
+struct Layout {
+	/// Units of the field columns
+	units: Vec<Option<String>>,
+	/// M-Bus channels with the columns
+	channels: Vec<u8>,
+	obis_columns: Vec<ObisCode>,
+}
+
+impl Layout {
+	fn new(telegram: &Telegram) -> Self {
+		let units = field_cells(telegram)
+			.into_iter()
+			.map(|cell| cell.unit.map(str::to_string))
+			.collect();
+		let channels = telegram
+			.mbus_devices
+			.iter()
+			.map(|device| device.channel)
+			.collect::<BTreeSet<_>>()
+			.into_iter()
+			.collect::<Vec<_>>();
+		let mut obis_columns = vec![];
+		for (obis, _) in raw_objects(telegram, &channels) {
+			if !obis_columns.contains(&obis) {
+				obis_columns.push(obis);
+			}
+		}
+		Self {
+			units,
+			channels,
+			obis_columns,
+		}
+	}
+
+	fn header(&self) -> Vec<String> {
+		let mut header = FIELD_COLUMNS
+			.into_iter()
+			.zip(&self.units)
+			.map(|(column, unit)| match unit {
+				Some(unit) => format!("{column} [{unit}]"),
+				None => column.to_string(),
+			})
+			.collect::<Vec<_>>();
+		for channel in &self.channels {
+			header.extend(MBUS_COLUMNS.iter().map(|column| format!("mbus_{channel}_{column}")));
+		}
+		header.extend(self.obis_columns.iter().map(ObisCode::to_string));
+		header.push(OBJECTS_COLUMN.to_string());
+		header
+	}
+
+	fn row(&self, telegram: &Telegram) -> Vec<String> {
+		let mut row = field_cells(telegram)
+			.into_iter()
+			.zip(&self.units)
+			.map(|(cell, unit)| {
+				if cell.value.is_empty() || cell.unit == unit.as_deref() {
+					cell.value
+				} else {
+					format!("{}*{}", cell.value, cell.unit.unwrap_or_default())
+				}
+			})
+			.collect::<Vec<_>>();
+		for &channel in &self.channels {
+			match telegram.mbus_device(channel) {
+				Some(device) => row.extend(mbus_cells(device)),
+				None => row.extend(MBUS_COLUMNS.map(|_| String::new())),
+			}
+		}
+		let mut obis_cells = vec![String::new(); self.obis_columns.len()];
+		let mut objects = vec![];
+		for (obis, value) in raw_objects(telegram, &self.channels) {
+			match self.obis_columns.iter().position(|column| *column == obis) {
+				Some(i) if obis_cells[i].is_empty() => obis_cells[i] = value,
+				_ => objects.push(format!("{obis}{value}")),
+			}
+		}
+		row.extend(obis_cells);
+		row.push(objects.join("\n"));
+		row
+	}
+}
+
+/// Reads the telegrams from the CSV produced by [CsvWriter]
+///
+/// The fields are restored from the corresponding columns with the unit from the column name unless the value has its
+/// own. The objects from the OBIS code and `objects` columns are decoded the same way as in the telegram, so only they end
+/// up in [Telegram::objects], and the ones that fail to decode are recorded in [Telegram::field_errors] as in the lenient
+/// mode. The CRC of the original telegram is not stored, only [Telegram::crc_valid] is. Empty cells
+/// are read as missing values, so an empty equipment id becomes `None`. Unknown columns are ignored.
+pub struct CsvReader<R> {
+	reader: csv::Reader<R>,
+	/// Names of the columns along with their meaning
+	columns: Vec<(String, Column)>,
+	record: csv::StringRecord,
+}
+
+impl<R: io::Read> CsvReader<R> {
+	/// Create the reader, reads the header from the source
+	pub fn new(src: R) -> Result<Self, Error> {
+		let mut reader = csv::Reader::from_reader(src);
+		let columns = reader
+			.headers()?
+			.iter()
+			.map(|name| (name.to_string(), Column::parse(name)))
+			.collect();
+		Ok(Self {
+			reader,
+			columns,
+			record: csv::StringRecord::new(),
+		})
+	}
+
+	fn decode(&self) -> Result<Telegram, Error> {
+		let mut telegram = Telegram::default();
+		let mut units = vec![];
+		for ((name, column), value) in self.columns.iter().zip(&self.record) {
+			let invalid = |_| Error::InvalidCsvValue {
+				column: name.to_string(),
+				value: value.to_string(),
+			};
+			match column {
+				Column::Field(field, unit) => {
+					set_field(&mut telegram, field, value, unit.as_deref()).map_err(invalid)?;
+				}
+				Column::Mbus(_, _) if value.is_empty() => {}
+				Column::Mbus(channel, "unit") => units.push((*channel, value)),
+				Column::Mbus(channel, column) => {
+					let device = match telegram.mbus_devices.iter().position(|device| device.channel == *channel) {
+						Some(i) => &mut telegram.mbus_devices[i],
+						None => {
+							telegram.mbus_devices.push(MbusDevice::new(*channel));
+							telegram.mbus_devices.last_mut().expect("Just pushed")
+						}
+					};
+					set_mbus_field(device, column, value).map_err(invalid)?;
+				}
+				Column::Obis(_) if value.is_empty() => {}
+				Column::Obis(obis) => {
+					let line = format!("{obis}{value}");
+					telegram.apply_raw_line(line.as_bytes()).map_err(|_| invalid(InvalidValue))?;
+				}
+				Column::Objects => {
+					for line in value.lines() {
+						telegram.apply_raw_line(line.as_bytes()).map_err(|_| invalid(InvalidValue))?;
+					}
+				}
+				Column::Ignored => {}
+			}
+		}
+		for (channel, unit) in units {
+			let value = telegram
+				.mbus_devices
+				.iter_mut()
+				.find(|device| device.channel == channel)
+				.and_then(|device| device.capture_value.as_mut());
+			if let Some(value) = value {
+				value.unit = Some(unit.to_string());
+			}
+		}
+		Ok(telegram)
+	}
+}
+
+impl<R: io::Read> Iterator for CsvReader<R> {
+	type Item = Result<Telegram, Error>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		match self.reader.read_record(&mut self.record) {
+			Ok(true) => Some(self.decode()),
+			Ok(false) => None,
+			Err(e) => Some(Err(e.into())),
+		}
+	}
+}
+
+/// Meaning of the CSV column determined from its name
+enum Column {
+	/// Field column with the unit from its name
+	Field(&'static str, Option<String>),
+	Mbus(u8, &'static str),
+	Obis(ObisCode),
+	Objects,
+	Ignored,
+}
+
+impl Column {
+	fn parse(name: &str) -> Self {
+		let (field, unit) = match name.strip_suffix(']').and_then(|rest| rest.split_once(" [")) {
+			Some((field, unit)) => (field, Some(unit)),
+			None => (name, None),
+		};
+		if let Some(field) = FIELD_COLUMNS.into_iter().find(|&column| column == field) {
+			return Column::Field(field, unit.map(str::to_string));
+		}
+		if name == OBJECTS_COLUMN {
+			return Column::Objects;
+		}
+		let mbus = name
+			.strip_prefix("mbus_")
+			.and_then(|rest| rest.split_once('_'))
+			.and_then(|(channel, column)| {
+				let column = MBUS_COLUMNS.into_iter().find(|&known| known == column)?;
+				Some(Column::Mbus(channel.parse().ok()?, column))
+			});
+		mbus
+			.or_else(|| name.parse().ok().map(Column::Obis))
+			.unwrap_or(Column::Ignored)
+	}
+}
+
+/// Marker of the value that can't be decoded, the column context is added by the caller
+struct InvalidValue;
+
+/// Value of the field column along with the unit of the number, the specification one if the number is missing
+struct Cell<'t> {
+	value: String,
+	unit: Option<&'t str>,
+}
+
+impl From<String> for Cell<'_> {
+	fn from(value: String) -> Self {
+		Self { value, unit: None }
+	}
+}
+
+/// Cells of the field columns in the order of [FIELD_COLUMNS]
+fn field_cells(telegram: &Telegram) -> [Cell<'_>; 37] {
+	[
+		telegram.ident.clone().into(),
+		text(&telegram.version).into(),
+		date(&telegram.electricity_date).into(),
+		text(&telegram.electricity_equipment_id).into(),
+		number(&telegram.electricity_consumed_tariff_1, "kWh"),
+		number(&telegram.electricity_consumed_tariff_2, "kWh"),
+		number(&telegram.electricity_generated_tariff_1, "kWh"),
+		number(&telegram.electricity_generated_tariff_2, "kWh"),
+		telegram
+			.current_tariff
+			.as_deref()
+			.map(|tariff| OctetString(tariff).to_string())
+			.unwrap_or_default()
+			.into(),
+		number(&telegram.power, "kW"),
+		number(&telegram.return_power, "kW"),
+		text(&telegram.power_failure_count).into(),
+		text(&telegram.long_power_failure_count).into(),
+		text(&telegram.voltage_sag_l1_count).into(),
+		text(&telegram.voltage_sag_l2_count).into(),
+		text(&telegram.voltage_sag_l3_count).into(),
+		text(&telegram.voltage_swell_l1_count).into(),
+		text(&telegram.voltage_swell_l2_count).into(),
+		text(&telegram.voltage_swell_l3_count).into(),
+		number(&telegram.voltage_l1, "V"),
+		number(&telegram.voltage_l2, "V"),
+		number(&telegram.voltage_l3, "V"),
+		number(&telegram.current_l1, "A"),
+		number(&telegram.current_l2, "A"),
+		number(&telegram.current_l3, "A"),
+		number(&telegram.power_l1, "kW"),
+		number(&telegram.power_l2, "kW"),
+		number(&telegram.power_l3, "kW"),
+		number(&telegram.return_power_l1, "kW"),
+		number(&telegram.return_power_l2, "kW"),
+		number(&telegram.return_power_l3, "kW"),
+		text(&telegram.emucs_version).into(),
+		number(&telegram.current_average_demand, "kW"),
+		text(&telegram.breaker_state).into(),
+		number(&telegram.limiter_threshold, "kW"),
+		number(&telegram.fuse_supervision_threshold, "A"),
+		telegram.crc_valid.to_string().into(),
+	]
+}
+
+/// Inverse of [field_cells]
+fn set_field(telegram: &mut Telegram, column: &str, value: &str, unit: Option<&str>) -> Result<(), InvalidValue> {
+	match column {
+		"ident" => telegram.ident = value.to_string(),
+		"version" => telegram.version = parse_text(value),
+		"electricity_date" => telegram.electricity_date = parse_date(value)?,
+		"electricity_equipment_id" => telegram.electricity_equipment_id = parse_text(value),
+		"electricity_consumed_tariff_1" => telegram.electricity_consumed_tariff_1 = parse_number(value, unit)?,
+		"electricity_consumed_tariff_2" => telegram.electricity_consumed_tariff_2 = parse_number(value, unit)?,
+		"electricity_generated_tariff_1" => telegram.electricity_generated_tariff_1 = parse_number(value, unit)?,
+		"electricity_generated_tariff_2" => telegram.electricity_generated_tariff_2 = parse_number(value, unit)?,
+		"current_tariff" => {
+			telegram.current_tariff = match value {
+				"" => None,
+				value => Some(parse_octet_string(value.as_bytes()).ok_or(InvalidValue)?),
+			}
+		}
+		"power" => telegram.power = parse_number(value, unit)?,
+		"return_power" => telegram.return_power = parse_number(value, unit)?,
+		"power_failure_count" => telegram.power_failure_count = parse(value)?,
+		"long_power_failure_count" => telegram.long_power_failure_count = parse(value)?,
+		"voltage_sag_l1_count" => telegram.voltage_sag_l1_count = parse(value)?,
+		"voltage_sag_l2_count" => telegram.voltage_sag_l2_count = parse(value)?,
+		"voltage_sag_l3_count" => telegram.voltage_sag_l3_count = parse(value)?,
+		"voltage_swell_l1_count" => telegram.voltage_swell_l1_count = parse(value)?,
+		"voltage_swell_l2_count" => telegram.voltage_swell_l2_count = parse(value)?,
+		"voltage_swell_l3_count" => telegram.voltage_swell_l3_count = parse(value)?,
+		"voltage_l1" => telegram.voltage_l1 = parse_number(value, unit)?,
+		"voltage_l2" => telegram.voltage_l2 = parse_number(value, unit)?,
+		"voltage_l3" => telegram.voltage_l3 = parse_number(value, unit)?,
+		"current_l1" => telegram.current_l1 = parse_number(value, unit)?,
+		"current_l2" => telegram.current_l2 = parse_number(value, unit)?,
+		"current_l3" => telegram.current_l3 = parse_number(value, unit)?,
+		"power_l1" => telegram.power_l1 = parse_number(value, unit)?,
+		"power_l2" => telegram.power_l2 = parse_number(value, unit)?,
+		"power_l3" => telegram.power_l3 = parse_number(value, unit)?,
+		"return_power_l1" => telegram.return_power_l1 = parse_number(value, unit)?,
+		"return_power_l2" => telegram.return_power_l2 = parse_number(value, unit)?,
+		"return_power_l3" => telegram.return_power_l3 = parse_number(value, unit)?,
+		"emucs_version" => telegram.emucs_version = parse_text(value),
+		"current_average_demand" => telegram.current_average_demand = parse_number(value, unit)?,
+		"breaker_state" => telegram.breaker_state = parse(value)?,
+		"limiter_threshold" => telegram.limiter_threshold = parse_number(value, unit)?,
+		"fuse_supervision_threshold" => telegram.fuse_supervision_threshold = parse_number(value, unit)?,
+		"crc_valid" => telegram.crc_valid = value.parse().map_err(|_| InvalidValue)?,
+		_ => {}
+	}
+	Ok(())
+}
+
+fn mbus_cells(device: &MbusDevice) -> [String; 6] {
+	[
+		text(&device.device_type.map(u8::from)),
+		text(&device.equipment_id),
+		text(&device.valve_position),
+		date(&device.capture_date),
+		device
+			.capture_value
+			.as_ref()
+			.map(|value| value.value.to_string())
+			.unwrap_or_default(),
+		device
+			.capture_value
+			.as_ref()
+			.and_then(|value| value.unit.clone())
+			.unwrap_or_default(),
+	]
+}
+
+/// Inverse of [mbus_cells] except for the unit that's set separately
+fn set_mbus_field(device: &mut MbusDevice, column: &str, value: &str) -> Result<(), InvalidValue> {
+	match column {
+		"device_type" => device.device_type = parse::<u8>(value)?.map(MbusDeviceType::from),
+		"equipment_id" => device.equipment_id = parse_text(value),
+		"valve_position" => device.valve_position = parse(value)?,
+		"capture_date" => device.capture_date = parse_date(value)?,
+		"capture_value" => device.capture_value = parse(value)?.map(UnitValue::without_unit),
+		_ => {}
+	}
+	Ok(())
+}
+
+/// Objects of the telegram not written into the field and M-Bus columns, with their raw values
+///
+/// The objects that failed to decode are kept as is, so that their values aren't lost along with the empty fields.
+fn raw_objects(telegram: &Telegram, channels: &[u8]) -> Vec<(ObisCode, String)> {
+	let mut objects = telegram
+		.object_lines()
+		.split("\r\n")
+		.filter_map(|line| ObisCode::parse_prefix(line.as_bytes()))
+		.filter(|(obis, _)| !FIELD_OBIS.contains(obis) && !is_mbus_column(*obis, channels))
+		.map(|(obis, values)| (obis, String::from_utf8_lossy(values).into_owned()))
+		.collect::<Vec<_>>();
+	let failed = telegram
+		.objects
+		.iter()
+		.filter(|object| !object.decoded && telegram.field_errors.iter().any(|error| error.obis == object.obis));
+	for object in failed {
+		let values = object.values.iter().map(|value| format!("({value})")).collect::<String>();
+		objects.push((object.obis, values));
+	}
+	objects
+}
+
+/// Whether the object is written into the M-Bus columns of one of the channels
+fn is_mbus_column(obis: ObisCode, channels: &[u8]) -> bool {
+	obis.a == 0
+		&& channels.contains(&obis.b)
+		&& matches!((obis.c, obis.d, obis.e), (24, 1, 0) | (96, 1, 0) | (24, 4, 0) | (24, 2, 1))
+}
+
+fn text(value: &Option<impl Display>) -> String {
+	value.as_ref().map(ToString::to_string).unwrap_or_default()
+}
+
+fn number<'t>(value: &'t Option<UnitValue<impl Display>>, default_unit: &'static str) -> Cell<'t> {
+	match value {
+		Some(value) => Cell {
+			value: value.value.to_string(),
+			unit: value.unit.as_deref(),
+		},
+		None => Cell {
+			value: String::new(),
+			unit: Some(default_unit),
+		},
+	}
+}
+
+fn date(value: &Option<Tst>) -> String {
+	value.map(Tst::to_cet_rfc3339).unwrap_or_default()
+}
+
+fn parse_text(value: &str) -> Option<String> {
+	(!value.is_empty()).then(|| value.to_string())
+}
+
+fn parse<T: FromStr>(value: &str) -> Result<Option<T>, InvalidValue> {
+	if value.is_empty() {
+		return Ok(None);
+	}
+	value.parse().map(Some).map_err(|_| InvalidValue)
+}
+
+/// Parse the number with the unit of the column, unless it has its own as in `1500*W` or `1500*` for no unit
+fn parse_number<T: FromStr>(value: &str, unit: Option<&str>) -> Result<Option<UnitValue<T>>, InvalidValue> {
+	Ok(match parse::<UnitValue<T>>(value)? {
+		Some(UnitValue { value, unit: None }) => Some(UnitValue {
+			value,
+			unit: unit.map(str::to_string),
+		}),
+		Some(UnitValue { value, unit: Some(unit) }) if unit.is_empty() => Some(UnitValue::without_unit(value)),
+		value => value,
+	})
+}
+
+fn parse_date(value: &str) -> Result<Option<Tst>, InvalidValue> {
+	if value.is_empty() {
+		return Ok(None);
+	}
+	Tst::from_cet_rfc3339(value).map(Some).ok_or(InvalidValue)
+}
@@ -0,0 +1,99 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::{Error, MAX_LINE_LEN, ParseOptions, Telegram, TelegramParser};
+
+/// Push-style telegram decoder that is not tied to any IO
+///
+/// Feed it the bytes as they arrive from the transport, split at arbitrary boundaries, and it will return the telegrams
+/// completed so far. It has the same error recovery behavior as [TelegramReader](crate::TelegramReader).
+///
+/// ```
+/// use dsmr_parse::TelegramDecoder;
+///
+/// let mut decoder = TelegramDecoder::new();
+/// assert!(decoder.feed(b"/XMX5LGBBFG1009394887\r\n\r\n1-3:0.2").is_empty());
+/// let telegrams = decoder.feed(b".8(42)\r\n!0D7D\r\n");
+/// assert_eq!(1, telegrams.len());
+/// assert_eq!(Some("42"), telegrams[0].as_ref().unwrap().version.as_deref());
+/// ```
+pub struct TelegramDecoder {
+	line: Vec<u8>,
+	parser: TelegramParser,
+}
+
+impl TelegramDecoder {
+	pub fn new() -> Self {
+		Self::with_options(ParseOptions::default())
+	}
+
+	pub fn with_options(options: ParseOptions) -> Self {
+		Self {
+			line: Vec::with_capacity(64),
+			parser: TelegramParser::new(options),
+		}
+	}
+
+	/// Feed the next chunk of bytes to the decoder, returns the telegrams that were completed within this chunk
+	pub fn feed(&mut self, mut bytes: &[u8]) -> Vec<Result<Telegram, Error>> {
+		let mut out = vec![];
+		while !bytes.is_empty() {
+			let (consumed, res) = self.decode(bytes);
+			out.extend(res);
+			bytes = &bytes[consumed..];
+		}
+		out
+	}
+
+	/// Feed the bytes to the decoder until the first telegram is completed
+	///
+	/// Returns the number of bytes consumed from `bytes` and the completed telegram if any. The bytes that weren't consumed
+	/// belong to the next telegram and should be passed in the next call.
+	pub fn decode(&mut self, bytes: &[u8]) -> (usize, Option<Result<Telegram, Error>>) {
+		let mut consumed = 0;
+		for line in bytes.split_inclusive(|&b| b == b'\n') {
+			consumed += line.len();
+			let Some(line) = line.strip_suffix(b"\n") else {
+				self.push_bytes(line);
+				break;
+			};
+			self.push_bytes(line);
+			if self.line.ends_with(b"\r") {
+				self.line.pop();
+			}
+			let res = self.parser.push_line(&self.line);
+			self.line.clear();
+			if res.is_some() {
+				return (consumed, res);
+			}
+		}
+		(consumed, None)
+	}
+
+	/// Signal the end of the stream
+	///
+	/// Returns [Error::TruncatedTelegram] if the stream ended in the middle of a telegram. The incomplete line is discarded
+	/// and the decoder is ready for a new stream.
+	pub fn finish(&mut self) -> Option<Result<Telegram, Error>> {
+		self.line.clear();
+		self.parser.finish()
+	}
+
+	/// Number of bytes skipped before the header of the last telegram (e.g. the tail of a telegram the stream started in
+	/// the middle of), assuming CRLF line terminators
+	pub fn skipped_bytes(&self) -> usize {
+		self.parser.skipped_bytes()
+	}
+
+	/// Append the bytes to the current line, the excess over [MAX_LINE_LEN] is dropped, so the parser rejects the line
+	fn push_bytes(&mut self, bytes: &[u8]) {
+		let len = bytes.len().min((MAX_LINE_LEN + 1).saturating_sub(self.line.len()));
+		self.line.extend_from_slice(&bytes[..len]);
+	}
+}
+
+impl Default for TelegramDecoder {
+	fn default() -> Self {
+		Self::new()
+	}
+}
@@ -0,0 +1,119 @@
+use crate::{Error, ParseOptions, Telegram, TelegramDecoder};
+
+impl Telegram {
+	/// Try to read a single telegram from an [embedded_io::Read] source
+	///
+	/// Counterpart of [Telegram::read_from] for embedded targets. The data read beyond the end of the telegram is discarded,
+	/// use [EmbeddedTelegramReader] to continuously read telegrams from the same source.
+	pub fn read_from_embedded(src: impl embedded_io::Read) -> Result<Option<Self>, Error> {
+		EmbeddedTelegramReader::new(src).next().transpose()
+	}
+
+	/// Try to read a single telegram from an [embedded_io_async::Read] source
+	///
+	/// Async version of [Telegram::read_from_embedded].
+	#[cfg(feature = "embedded-io-async")]
+	pub async fn read_from_embedded_async(src: impl embedded_io_async::Read) -> Result<Option<Self>, Error> {
+		EmbeddedTelegramReader::new(src).next_async().await.transpose()
+	}
+}
+
+/// Continuous reader of telegrams from an `embedded-io` source (e.g. UART peripheral)
+///
+/// Reads the data in small chunks into the internal buffer and passes it to the [TelegramDecoder], so the bytes belonging to
+/// the next telegram are preserved between the calls. It has the same error recovery behavior as
+/// [TelegramReader](crate::TelegramReader).
+///
+/// Implements [Iterator] for [embedded_io::Read] sources, and provides [EmbeddedTelegramReader::next_async] for
+/// [embedded_io_async::Read] sources with the `embedded-io-async` feature enabled.
+pub struct EmbeddedTelegramReader<R> {
+	inner: R,
+	buf: [u8; 64],
+	pos: usize,
+	len: usize,
+	decoder: TelegramDecoder,
+}
+
+impl<R> EmbeddedTelegramReader<R> {
+	pub fn new(src: R) -> Self {
+		Self::with_options(src, ParseOptions::default())
+	}
+
+	pub fn with_options(src: R, options: ParseOptions) -> Self {
+		Self {
+			inner: src,
+			buf: [0; 64],
+			pos: 0,
+			len: 0,
+			decoder: TelegramDecoder::with_options(options),
+		}
+	}
+
+	/// Number of bytes skipped before the header of the last telegram, assuming CRLF line terminators
+	pub fn skipped_bytes(&self) -> usize {
+		self.decoder.skipped_bytes()
+	}
+
+	/// Return the underlying source, the data that is buffered but not yet decoded is lost
+	pub fn into_inner(self) -> R {
+		self.inner
+	}
+
+	/// Decode the buffered data, returns `Some` once a telegram is completed or failed to parse
+	fn decode_buffered(&mut self) -> Option<Result<Telegram, Error>> {
+		while self.pos < self.len {
+			let (consumed, res) = self.decoder.decode(&self.buf[self.pos..self.len]);
+			self.pos += consumed;
+			if res.is_some() {
+				return res;
+			}
+		}
+		None
+	}
+
+	/// Store the result of the read operation into the buffer, returns `false` on the end of the stream
+	fn handle_read<E: embedded_io::Error>(&mut self, res: Result<usize, E>) -> Result<bool, Error> {
+		let len = res.map_err(|e| Error::EmbeddedIo(e.kind()))?;
+		self.pos = 0;
+		self.len = len;
+		Ok(len > 0)
+	}
+}
+
+impl<R: embedded_io::Read> Iterator for EmbeddedTelegramReader<R> {
+	type Item = Result<Telegram, Error>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		loop {
+			if let Some(res) = self.decode_buffered() {
+				return Some(res);
+			}
+			let res = self.inner.read(&mut self.buf);
+			match self.handle_read(res) {
+				Ok(true) => {}
+				Ok(false) => return self.decoder.finish(),
+				Err(e) => return Some(Err(e)),
+			}
+		}
+	}
+}
+
+#[cfg(feature = "embedded-io-async")]
+impl<R: embedded_io_async::Read> EmbeddedTelegramReader<R> {
+	/// Read the next telegram, returns `None` on the end of the stream
+	///
+	/// Async counterpart of [Iterator::next].
+	pub async fn next_async(&mut self) -> Option<Result<Telegram, Error>> {
+		loop {
+			if let Some(res) = self.decode_buffered() {
+				return Some(res);
+			}
+			let res = self.inner.read(&mut self.buf).await;
+			match self.handle_read(res) {
+				Ok(true) => {}
+				Ok(false) => return self.decoder.finish(),
+				Err(e) => return Some(Err(e)),
+			}
+		}
+	}
+}
@@ -0,0 +1,173 @@
+use alloc::string::String;
+use core::fmt;
+use core::fmt::Write;
+
+use jiff::tz::TimeZone;
+
+use crate::{Telegram, Tst, UnitValue};
+
+impl Telegram {
+	/// Render the telegram as InfluxDB line protocol
+	///
+	/// The electricity readings are written as one line of the `measurement` tagged with the equipment id and the current
+	/// tariff. The field keys are the names of the [Telegram] fields without the `electricity_` prefix, the values are in the
+	/// units reported by the meter (kWh, kW, V, A). Every M-Bus device with a captured value gets its own line tagged with
+	/// its equipment id, channel and device type code, with the captured value (e.g. the gas volume) in the `value` field.
+	///
+	/// The timestamps in nanoseconds are derived from [Telegram::electricity_date] and [MbusDevice::capture_date](crate::MbusDevice::capture_date)
+	/// in the specified timezone using [Tst::to_jiff], the line is written without the timestamp if the date is missing.
+	///
+	/// ```
+	/// use dsmr_parse::Telegram;
+	///
+	/// let telegram = Telegram::read_from(
+	///     b"/XMX5LGBBFG1009394887\r\n\r\n1-3:0.2.8(50)\r\n0-0:1.0.0(190101125431W)\r\n1-0:1.8.1(004169.415*kWh)\r\n!3063\r\n".as_slice(),
+	/// )
+	/// .unwrap()
+	/// .unwrap();
+	/// let tz = jiff::tz::TimeZone::fixed(jiff::tz::offset(1));
+	/// assert_eq!("p1 consumed_tariff_1=4169.415 1546343671000000000\n", telegram.to_influx("p1", &tz));
+	/// ```
+	pub fn to_influx(&self, measurement: &str, timezone: &TimeZone) -> String {
+		let mut out = String::with_capacity(1024);
+		// writing to a String can't fail
+		let _ = self.write_influx(&mut out, measurement, timezone);
+		out
+	}
+
+	fn write_influx(&self, out: &mut String, measurement: &str, timezone: &TimeZone) -> fmt::Result {
+		let mut line = InfluxLine::new(out, measurement)?;
+		if let Some(id) = &self.electricity_equipment_id {
+			line.tag("equipment_id", id)?;
+		}
+		if let Some(tariff) = self.tariff() {
+			line.tag("tariff", &tariff)?;
+		}
+		for (key, value) in [
+			("consumed_tariff_1", &self.electricity_consumed_tariff_1),
+			("consumed_tariff_2", &self.electricity_consumed_tariff_2),
+			("generated_tariff_1", &self.electricity_generated_tariff_1),
+			("generated_tariff_2", &self.electricity_generated_tariff_2),
+			("power", &self.power),
+			("return_power", &self.return_power),
+			("power_l1", &self.power_l1),
+			("power_l2", &self.power_l2),
+			("power_l3", &self.power_l3),
+			("return_power_l1", &self.return_power_l1),
+			("return_power_l2", &self.return_power_l2),
+			("return_power_l3", &self.return_power_l3),
+			("voltage_l1", &self.voltage_l1),
+			("voltage_l2", &self.voltage_l2),
+			("voltage_l3", &self.voltage_l3),
+		] {
+			if let Some(UnitValue { value, .. }) = value {
+				line.field(key, value)?;
+			}
+		}
+		for (key, value) in [
+			("current_l1", &self.current_l1),
+			("current_l2", &self.current_l2),
+			("current_l3", &self.current_l3),
+		] {
+			if let Some(UnitValue { value, .. }) = value {
+				line.field(key, format_args!("{value}i"))?;
+			}
+		}
+		for (key, count) in [
+			("power_failure_count", self.power_failure_count),
+			("long_power_failure_count", self.long_power_failure_count),
+			("voltage_sag_l1_count", self.voltage_sag_l1_count),
+			("voltage_sag_l2_count", self.voltage_sag_l2_count),
+			("voltage_sag_l3_count", self.voltage_sag_l3_count),
+			("voltage_swell_l1_count", self.voltage_swell_l1_count),
+			("voltage_swell_l2_count", self.voltage_swell_l2_count),
+			("voltage_swell_l3_count", self.voltage_swell_l3_count),
+		] {
+			if let Some(count) = count {
+				line.field(key, format_args!("{count}i"))?;
+			}
+		}
+		line.end(self.electricity_date, timezone)?;
+
+		for device in &self.mbus_devices {
+			let Some(UnitValue { value, .. }) = &device.capture_value else {
+				continue;
+			};
+			let mut line = InfluxLine::new(out, measurement)?;
+			if let Some(id) = &device.equipment_id {
+				line.tag("equipment_id", id)?;
+			}
+			line.tag("channel", &device.channel)?;
+			if let Some(device_type) = device.device_type {
+				line.tag("device_type", &u8::from(device_type))?;
+			}
+			line.field("value", value)?;
+			line.end(device.capture_date, timezone)?;
+		}
+		Ok(())
+	}
+}
+
+/// Single line of the line protocol, the tags must be added before the fields
+///
+/// A line without fields is invalid, so it's removed on [InfluxLine::end] in that case.
+struct InfluxLine<'o> {
+	out: &'o mut String,
+	start: usize,
+	fields: usize,
+}
+
+impl<'o> InfluxLine<'o> {
+	fn new(out: &'o mut String, measurement: &str) -> Result<Self, fmt::Error> {
+		let start = out.len();
+		write!(out, "{}", Escaped(measurement, &[',', ' ']))?;
+		Ok(Self { out, start, fields: 0 })
+	}
+
+	/// Add the tag, the empty values are skipped as they are invalid in the line protocol
+	fn tag(&mut self, key: &str, value: &dyn fmt::Display) -> fmt::Result {
+		let mut escaped = String::new();
+		write!(escaped, "{value}")?;
+		if escaped.is_empty() {
+			return Ok(());
+		}
+		write!(self.out, ",{key}={}", Escaped(&escaped, &[',', '=', ' ']))
+	}
+
+	fn field(&mut self, key: &str, value: impl fmt::Display) -> fmt::Result {
+		let separator = if self.fields == 0 {
+			' '
+		} else {
+			','
+		};
+		self.fields += 1;
+		write!(self.out, "{separator}{key}={value}")
+	}
+
+	fn end(self, date: Option<Tst>, timezone: &TimeZone) -> fmt::Result {
+		if self.fields == 0 {
+			self.out.truncate(self.start);
+			return Ok(());
+		}
+		if let Some(date) = date.and_then(|date| date.to_jiff(timezone)) {
+			write!(self.out, " {}", date.timestamp().as_nanosecond())?;
+		}
+		self.out.push('\n');
+		Ok(())
+	}
+}
+
+/// String with the specified characters escaped with a backslash
+struct Escaped<'s>(&'s str, &'s [char]);
+
+impl fmt::Display for Escaped<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		for c in self.0.chars() {
+			if self.1.contains(&c) {
+				f.write_char('\\')?;
+			}
+			f.write_char(c)?;
+		}
+		Ok(())
+	}
+}
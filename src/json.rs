@@ -0,0 +1,175 @@
+use alloc::string::ToString;
+
+use jiff::tz::TimeZone;
+use serde_json::{Value, json};
+
+use crate::{Telegram, Tst, UnitValue};
+
+/// Version of the JSON schema produced by [Telegram::to_json]
+///
+/// It's incremented on every incompatible change of the schema, adding new keys is not considered one.
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+/// Rules of the `Europe/Amsterdam` and `Europe/Brussels` timezones, CET with the EU DST
+const CET_POSIX_TZ: &str = "CET-1CEST,M3.5.0,M10.5.0/3";
+
+impl Telegram {
+	/// Convert the telegram to JSON with a stable schema
+	///
+	/// Unlike the `serde` representation of [Telegram] that mirrors its fields, this schema is meant for the consumers that
+	/// don't know about the DSMR details. All keys are always present, missing values are `null`. The numbers are converted
+	/// to the base units according to the unit reported by the meter: energy in Wh, power in W, voltage in V, current in A,
+	/// durations in seconds. The M-Bus device values are passed as is along with their unit.
+	///
+	/// The timestamps are in the RFC 3339 format with the offset of the `Europe/Amsterdam` timezone that DSMR meters report
+	/// the local time in, e.g. `2019-01-01T12:54:31+01:00`. They are resolved using [Tst::to_jiff] with the CET/CEST rules,
+	/// so the DST flag of the meter only picks one of the ambiguous times at the end of DST, and the legacy timestamps
+	/// without the flag get the correct offset as well. The invalid timestamps are `null`.
+	///
+	/// ```json
+	/// {
+	///   "schema_version": 1,
+	///   "ident": "XMX5LGBBFG1098765432",
+	///   "dsmr_version": "42",
+	///   "timestamp": "2019-01-01T12:54:31+01:00",
+	///   "equipment_id": "E0034567890123456",
+	///   "tariff": 1,
+	///   "message": "",
+	///   "energy": {
+	///     "consumed_tariff_1": 4169415.0, "consumed_tariff_2": 4884452.0,
+	///     "generated_tariff_1": 0.0, "generated_tariff_2": 0.0
+	///   },
+	///   "power": { "consumed": 741.0, "generated": 0.0 },
+	///   "phases": {
+	///     "l1": {
+	///       "voltage": null, "current": 4.0, "power_consumed": 741.0, "power_generated": 0.0,
+	///       "voltage_sags": 0, "voltage_swells": 0
+	///     },
+	///     "l2": { ... },
+	///     "l3": { ... }
+	///   },
+	///   "power_failures": { "count": 2, "long_count": 0, "log": [{ "end": "...", "duration": 240 }] },
+	///   "demand": {
+	///     "current_average": null,
+	///     "maximum_month": { "timestamp": "...", "demand": 2589.0 },
+	///     "history": [{ "month": "...", "timestamp": "...", "demand": 3695.0 }]
+	///   },
+	///   "breaker_state": null,
+	///   "limiter_threshold": null,
+	///   "fuse_supervision_threshold": null,
+	///   "mbus": [{
+	///     "channel": 1, "device_type": 3, "equipment_id": "G0062749506872590", "valve_position": null,
+	///     "timestamp": "2019-01-01T12:00:00+01:00", "value": 1619.203, "unit": "m3"
+	///   }]
+	/// }
+	/// ```
+	pub fn to_json(&self) -> Value {
+		let timezone = TimeZone::posix(CET_POSIX_TZ).expect("Valid POSIX TZ string");
+		let timestamp = |tst: &Tst| {
+			tst.to_jiff(&timezone)
+				.map(|zoned| zoned.timestamp().display_with_offset(zoned.offset()).to_string())
+		};
+		let phase = |voltage: &Option<UnitValue<f64>>,
+		             current: &Option<UnitValue<u16>>,
+		             power_consumed: &Option<UnitValue<f64>>,
+		             power_generated: &Option<UnitValue<f64>>,
+		             voltage_sags: Option<u32>,
+		             voltage_swells: Option<u32>| {
+			json!({
+				"voltage": voltage.as_ref().map(base_unit),
+				"current": current.as_ref().map(base_unit),
+				"power_consumed": power_consumed.as_ref().map(base_unit),
+				"power_generated": power_generated.as_ref().map(base_unit),
+				"voltage_sags": voltage_sags,
+				"voltage_swells": voltage_swells,
+			})
+		};
+		json!({
+			"schema_version": JSON_SCHEMA_VERSION,
+			"ident": self.ident,
+			"dsmr_version": self.version,
+			"timestamp": self.electricity_date.as_ref().and_then(timestamp),
+			"equipment_id": self.electricity_equipment_id,
+			"tariff": self.tariff(),
+			"message": self.message,
+			"energy": {
+				"consumed_tariff_1": self.electricity_consumed_tariff_1.as_ref().map(base_unit),
+				"consumed_tariff_2": self.electricity_consumed_tariff_2.as_ref().map(base_unit),
+				"generated_tariff_1": self.electricity_generated_tariff_1.as_ref().map(base_unit),
+				"generated_tariff_2": self.electricity_generated_tariff_2.as_ref().map(base_unit),
+			},
+			"power": {
+				"consumed": self.power.as_ref().map(base_unit),
+				"generated": self.return_power.as_ref().map(base_unit),
+			},
+			"phases": {
+				"l1": phase(
+					&self.voltage_l1,
+					&self.current_l1,
+					&self.power_l1,
+					&self.return_power_l1,
+					self.voltage_sag_l1_count,
+					self.voltage_swell_l1_count,
+				),
+				"l2": phase(
+					&self.voltage_l2,
+					&self.current_l2,
+					&self.power_l2,
+					&self.return_power_l2,
+					self.voltage_sag_l2_count,
+					self.voltage_swell_l2_count,
+				),
+				"l3": phase(
+					&self.voltage_l3,
+					&self.current_l3,
+					&self.power_l3,
+					&self.return_power_l3,
+					self.voltage_sag_l3_count,
+					self.voltage_swell_l3_count,
+				),
+			},
+			"power_failures": {
+				"count": self.power_failure_count,
+				"long_count": self.long_power_failure_count,
+				"log": self.power_failure_log.iter().map(|entry| json!({
+					"end": timestamp(&entry.end_date),
+					"duration": entry.duration.value,
+				})).collect::<Value>(),
+			},
+			"demand": {
+				"current_average": self.current_average_demand.as_ref().map(base_unit),
+				"maximum_month": self.maximum_demand_month.as_ref().map(|peak| json!({
+					"timestamp": timestamp(&peak.date),
+					"demand": base_unit(&peak.demand),
+				})),
+				"history": self.maximum_demand_history.iter().map(|entry| json!({
+					"month": timestamp(&entry.month),
+					"timestamp": timestamp(&entry.date),
+					"demand": base_unit(&entry.demand),
+				})).collect::<Value>(),
+			},
+			"breaker_state": self.breaker_state,
+			"limiter_threshold": self.limiter_threshold.as_ref().map(base_unit),
+			"fuse_supervision_threshold": self.fuse_supervision_threshold.as_ref().map(base_unit),
+			"mbus": self.mbus_devices.iter().map(|device| json!({
+				"channel": device.channel,
+				"device_type": device.device_type.map(u8::from),
+				"equipment_id": device.equipment_id,
+				"valve_position": device.valve_position,
+				"timestamp": device.capture_date.as_ref().and_then(timestamp),
+				"value": device.capture_value.as_ref().map(|value| value.value),
+				"unit": device.capture_value.as_ref().and_then(|value| value.unit.as_deref()),
+			})).collect::<Value>(),
+		})
+	}
+}
+
+/// Value converted from the prefixed unit reported by the meter (`kWh`, `kW`, etc.) to the base one
+fn base_unit<T: Copy + Into<f64>>(value: &UnitValue<T>) -> f64 {
+	let number = value.value.into();
+	match value.unit.as_deref() {
+		Some(unit) if unit.len() > 1 && unit.starts_with('k') => number * 1e3,
+		Some(unit) if unit.len() > 1 && unit.starts_with('M') => number * 1e6,
+		_ => number,
+	}
+}
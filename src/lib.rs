@@ -2,45 +2,98 @@
 //!
 //! A small library for parsing DSMR 5 (Dutch Smart Meter Requirements) telegrams from Dutch electricity meters.
 //!
-//! The central struct is [Telegram] with its single method [Telegram::read_from]. Pass anything that implements `std::io::Read`
-//! to it, and it will try to read a valid and conforming DSMR telegram with the correct CRC from it.
+//! The central struct is [Telegram] holding the decoded fields, the M-Bus devices and the raw COSEM objects of a telegram.
+//! The simplest way to get one is [Telegram::read_from]: pass anything that implements `std::io::BufRead` to it, and it
+//! will try to read a valid and conforming DSMR telegram with the correct CRC from it. Depending on the source there are
+//! also:
+//! * [TelegramReader] to continuously read telegrams from the same `std::io::BufRead` source;
+//! * `Telegram::read_from_async` and `TelegramStream` providing the same for `tokio::io::AsyncBufRead` sources with the
+//!   `tokio` feature;
+//! * IO-agnostic [TelegramDecoder] for the transports that deliver the data in arbitrary chunks (MQTT, websockets, DMA
+//!   buffers, etc.);
+//! * `EmbeddedTelegramReader` for the `embedded-io` and `embedded-io-async` sources with the corresponding features;
+//! * [TelegramRef] providing a zero-copy view of a complete telegram that's already in memory, it borrows from the input
+//!   buffer and decodes the values on demand.
+//!
+//! The parsing can be relaxed with [ParseOptions] passed to the `with_options` constructors. In the opposite direction
+//! [Telegram] implements `Display` writing a valid telegram with the correct CRC. The `serde` feature adds `Serialize` and
+//! `Deserialize` implementations for [Telegram] and the types of its fields, while the `json` feature provides
+//! `Telegram::to_json` producing JSON with a stable versioned schema. With the `influx` feature `Telegram::to_influx`
+//! renders the telegram as InfluxDB line protocol, and with the `openmetrics` feature `Telegram::to_openmetrics` renders it
+//! in the OpenMetrics format for Prometheus. The `mqtt` feature maps the telegram to MQTT messages including the Home
+//! Assistant discovery configs. The `csv` feature adds `CsvWriter` and `CsvReader` to store the telegrams as CSV for the
+//! spreadsheet analysis and replay them later.
 //!
 //! ## Usage
 //!
 //! ### Reading from a serial port
 //!
 //! ```no_run
+//! # #[cfg(feature = "std")] {
+//! use std::io::BufReader;
+//!
 //! use dsmr_parse::Telegram;
 //!
 //! // Open serial port (example path)
 //! let port = serialport::new("/dev/ttyUSB0", 115_200);
 //!
 //! // Parse telegram
-//! match Telegram::read_from(port.open().unwrap()) {
+//! match Telegram::read_from(BufReader::new(port.open().unwrap())) {
 //!     Ok(Some(telegram)) => println!("Read telegram: {telegram:?}"),
-//!     Ok(None) => eprintln!("No complete telegram read"),
+//!     Ok(None) => eprintln!("No telegram read"),
 //!     Err(e) => eprintln!("Parse error: {e}"),
 //! }
+//! # }
 //! ```
 //!
 //! ### Reading from a byte slice
 //!
 //! ```
+//! # #[cfg(feature = "std")] {
 //! use dsmr_parse::Telegram;
 //!
 //! let telegram_data = b"/XMX5LGBBFG1009394887\r\n\r\n1-3:0.2.8(42)\r\n0-0:1.0.0(190101125431W)\r\n1-0:1.8.1(004169.415*kWh)\r\n!1234\r\n";
 //!
 //! match Telegram::read_from(telegram_data.as_slice()) {
 //!     Ok(Some(telegram)) => println!("Read telegram: {telegram:?}"),
-//!     Ok(None) => println!("No complete telegram in data"),
+//!     Ok(None) => println!("No telegram in data"),
 //!     Err(e) => eprintln!("Parse error: {e}"),
 //! }
+//! # }
 //! ```
 //!
+//! ### Reading a stream of telegrams
+//!
+//! ```no_run
+//! # #[cfg(feature = "std")] {
+//! use std::io::BufReader;
+//!
+//! use dsmr_parse::TelegramReader;
+//!
+//! let port = serialport::new("/dev/ttyUSB0", 115_200);
+//!
+//! for telegram in TelegramReader::new(BufReader::new(port.open().unwrap())) {
+//!     match telegram {
+//!         Ok(telegram) => println!("Read telegram: {telegram:?}"),
+//!         Err(e) => eprintln!("Parse error: {e}"),
+//!     }
+//! }
+//! # }
+//! ```
+//!
+//! ## `no_std` support
+//!
+//! The crate is `no_std` compatible (it still requires `alloc`) when built with `default-features = false`. The parser core
+//! is then available through [TelegramDecoder], while `std::io` integration like [Telegram::read_from] and [TelegramReader]
+//! requires the `std` feature, which is enabled by default.
+//!
 //! ## Potential Pitfalls
 //!
 //! - CRC Validation: telegrams with incorrect CRC checksums will be rejected. Ensure data integrity during transmission,
-//!   especially when reading from serial ports with poor connections.
+//!   especially when reading from serial ports with poor connections. Legacy DSMR 2.2/3.0 telegrams end with a bare `!`
+//!   without the CRC, such telegrams are accepted only if they have none of the DSMR 4.0+ markers: the version (`0.2.8`),
+//!   the e-MUCS version (`96.1.4`) or the timestamps with the DST flag. Set [ParseOptions::crc] to [CrcMode::Accept] or
+//!   [CrcMode::Ignore] to accept the telegrams with invalid CRC anyway, e.g. behind the splitters known to corrupt it.
 //! - Serial Port Configuration: DSMR meters typically use 115200 baud, 8N1: 8 data bits, no parity, 1 stop bit. Incorrect
 //!   settings will result in garbled data and parse errors.
 //!
@@ -48,11 +101,52 @@
 //!
 //! [P1 Companion Standard 5.0.2](https://www.netbeheernederland.nl/publicatie/dsmr-502-p1-companion-standard)
 
+#![no_std]
+
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(feature = "tokio")]
+pub use async_reader::*;
+#[cfg(feature = "csv")]
+pub use csv_io::*;
+pub use decoder::*;
+#[cfg(feature = "embedded-io")]
+pub use embedded::*;
+#[cfg(feature = "json")]
+pub use json::*;
+#[cfg(feature = "mqtt")]
+pub use mqtt::*;
+pub use obis::*;
+pub use options::*;
+#[cfg(feature = "std")]
+pub use reader::*;
 pub use telegram::*;
 pub use tst::*;
 pub use unit_value::*;
 
+#[cfg(feature = "tokio")]
+mod async_reader;
+#[cfg(feature = "csv")]
+mod csv_io;
+mod decoder;
+#[cfg(feature = "embedded-io")]
+mod embedded;
+#[cfg(feature = "influx")]
+mod influx;
+#[cfg(feature = "json")]
+mod json;
+#[cfg(feature = "std")]
 mod line_reader;
+#[cfg(feature = "mqtt")]
+mod mqtt;
+mod obis;
+#[cfg(feature = "openmetrics")]
+mod openmetrics;
+mod options;
+#[cfg(feature = "std")]
+mod reader;
 mod telegram;
 mod tst;
 mod unit_value;
@@ -1,33 +1,62 @@
-use std::io;
+use alloc::vec::Vec;
+use std::io::{self, BufRead};
 
+use crate::MAX_LINE_LEN;
+
+/// Reads lines from a [BufRead] source without consuming anything past the end of the current line
 pub struct LineReader<B> {
 	inner: B,
+	line: Vec<u8>,
+	complete: bool,
 }
 
-impl<B: Iterator<Item = io::Result<u8>>> LineReader<B> {
+impl<B: BufRead> LineReader<B> {
 	pub fn new(inner: B) -> Self {
-		Self { inner }
+		Self {
+			inner,
+			line: Vec::with_capacity(64),
+			complete: false,
+		}
 	}
-}
 
-impl<B: Iterator<Item = io::Result<u8>>> Iterator for LineReader<B> {
-	type Item = io::Result<Vec<u8>>;
+	pub fn get_ref(&self) -> &B {
+		&self.inner
+	}
 
-	fn next(&mut self) -> Option<Self::Item> {
-		let mut line = Vec::with_capacity(64);
-		loop {
-			match self.inner.next()? {
-				Ok(b) => {
-					if b == b'\n' {
-						if line.ends_with(b"\r") {
-							line.pop();
-						}
-						break Some(Ok(line));
-					}
-					line.push(b);
-				}
-				Err(e) => break Some(Err(e)),
-			}
+	pub fn get_mut(&mut self) -> &mut B {
+		&mut self.inner
+	}
+
+	pub fn into_inner(self) -> B {
+		self.inner
+	}
+
+	/// Read the next line without the line terminator, returns `None` when the source is exhausted
+	///
+	/// The incomplete line is kept between the calls, so the reading can continue after an IO error (e.g. timeout). Only
+	/// the first [MAX_LINE_LEN] + 1 bytes of the line are kept, so the parser can reject it without buffering it whole.
+	pub fn read_line(&mut self) -> Option<io::Result<&[u8]>> {
+		if self.complete {
+			self.line.clear();
+			self.complete = false;
+		}
+		while !self.complete {
+			let buf = match self.inner.fill_buf() {
+				Ok([]) => return None,
+				Ok(buf) => buf,
+				Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+				Err(e) => return Some(Err(e)),
+			};
+			let (chunk, complete) = match buf.iter().position(|&b| b == b'\n') {
+				Some(pos) => (&buf[..pos], true),
+				None => (buf, false),
+			};
+			let len = chunk.len().min((MAX_LINE_LEN + 1).saturating_sub(self.line.len()));
+			self.line.extend_from_slice(&chunk[..len]);
+			let consumed = chunk.len() + usize::from(complete);
+			self.inner.consume(consumed);
+			self.complete = complete;
 		}
+		Some(Ok(self.line.strip_suffix(b"\r").unwrap_or(&self.line)))
 	}
 }
@@ -0,0 +1,312 @@
+use alloc::borrow::ToOwned;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use core::convert::Infallible;
+
+use serde_json::{Map, Value, json};
+
+use crate::{MbusDeviceType, Telegram, UnitValue};
+
+/// Options of the MQTT mapping of the telegram
+///
+/// ```
+/// use dsmr_parse::{MqttOptions, TopicLayout};
+///
+/// let options = MqttOptions {
+///     base_topic: "home/p1".to_string(),
+///     layout: TopicLayout::Json,
+///     ..MqttOptions::default()
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MqttOptions {
+	/// Prefix of the state topics, `dsmr` by default
+	pub base_topic: String,
+	/// Prefix of the Home Assistant discovery topics, `homeassistant` by default
+	pub discovery_prefix: String,
+	/// How the values are mapped to the state topics
+	pub layout: TopicLayout,
+}
+
+impl Default for MqttOptions {
+	fn default() -> Self {
+		Self {
+			base_topic: "dsmr".to_string(),
+			discovery_prefix: "homeassistant".to_string(),
+			layout: TopicLayout::default(),
+		}
+	}
+}
+
+/// Mapping of the telegram values to the MQTT state topics
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TopicLayout {
+	/// Every value is published to its own `<base_topic>/<key>` topic as a plain number
+	#[default]
+	PerField,
+	/// All values are published as a single JSON object to the `<base_topic>/state` topic
+	Json,
+}
+
+/// Single MQTT message to publish
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MqttMessage {
+	pub topic: String,
+	pub payload: String,
+	/// Whether the broker should retain the message, set for the discovery configs
+	pub retain: bool,
+}
+
+/// Destination of the MQTT messages, implement it on top of the MQTT client of choice
+///
+/// `Vec<MqttMessage>` implements it by collecting the messages, which is handy for testing without a broker.
+pub trait MqttPublisher {
+	type Error;
+
+	fn publish(&mut self, message: MqttMessage) -> Result<(), Self::Error>;
+}
+
+impl MqttPublisher for Vec<MqttMessage> {
+	type Error = Infallible;
+
+	fn publish(&mut self, message: MqttMessage) -> Result<(), Self::Error> {
+		self.push(message);
+		Ok(())
+	}
+}
+
+impl Telegram {
+	/// State messages with the values of the telegram, see [TopicLayout] for the topics
+	///
+	/// The keys are the names of the [Telegram] fields, M-Bus device values get the `mbus_<channel>` key. Only the populated
+	/// fields are included.
+	pub fn mqtt_state_messages(&self, options: &MqttOptions) -> Vec<MqttMessage> {
+		let sensors = self.sensors();
+		match options.layout {
+			TopicLayout::PerField => sensors
+				.into_iter()
+				.map(|sensor| MqttMessage {
+					topic: format!("{}/{}", options.base_topic, sensor.key),
+					payload: sensor.value.to_string(),
+					retain: false,
+				})
+				.collect(),
+			TopicLayout::Json => {
+				let state = sensors
+					.into_iter()
+					.map(|sensor| (sensor.key, sensor.value))
+					.collect::<Map<_, _>>();
+				vec![MqttMessage {
+					topic: format!("{}/state", options.base_topic),
+					payload: Value::Object(state).to_string(),
+					retain: false,
+				}]
+			}
+		}
+	}
+
+	/// Home Assistant MQTT discovery configs for the values of the telegram
+	///
+	/// Every populated value becomes a sensor with the `device_class` and `state_class` matching its kind and the unit taken
+	/// from [UnitValue::unit]. The sensors are grouped into the device identified by the equipment id of the meter (or the
+	/// telegram ident if it's missing). The configs are meant to be published once, e.g. for the first telegram.
+	pub fn mqtt_discovery_messages(&self, options: &MqttOptions) -> Vec<MqttMessage> {
+		let node_id = node_id(self.electricity_equipment_id.as_deref().unwrap_or(&self.ident));
+		let device = json!({
+			"identifiers": [node_id],
+			"name": "DSMR meter",
+			"model": self.ident,
+			"sw_version": self.version,
+		});
+		self
+			.sensors()
+			.into_iter()
+			.map(|sensor| {
+				let mut config = json!({
+					"name": sensor.name,
+					"unique_id": format!("{node_id}_{}", sensor.key),
+					"device": device,
+				});
+				let (state_topic, value_template) = match options.layout {
+					TopicLayout::PerField => (format!("{}/{}", options.base_topic, sensor.key), None),
+					TopicLayout::Json => (
+						format!("{}/state", options.base_topic),
+						Some(format!("{{{{ value_json.{} }}}}", sensor.key)),
+					),
+				};
+				config["state_topic"] = state_topic.into();
+				if let Some(value_template) = value_template {
+					config["value_template"] = value_template.into();
+				}
+				if let Some(device_class) = sensor.device_class {
+					config["device_class"] = device_class.into();
+				}
+				if let Some(state_class) = sensor.state_class {
+					config["state_class"] = state_class.into();
+				}
+				if let Some(unit) = sensor.unit {
+					// Home Assistant only accepts the superscript volume units
+					config["unit_of_measurement"] = match unit {
+						"m3" => "m³",
+						unit => unit,
+					}
+					.into();
+				}
+				MqttMessage {
+					topic: format!("{}/sensor/{node_id}/{}/config", options.discovery_prefix, sensor.key),
+					payload: config.to_string(),
+					retain: true,
+				}
+			})
+			.collect()
+	}
+
+	/// Publish the state messages of the telegram, see [Telegram::mqtt_state_messages]
+	pub fn publish_mqtt<P: MqttPublisher>(&self, publisher: &mut P, options: &MqttOptions) -> Result<(), P::Error> {
+		self
+			.mqtt_state_messages(options)
+			.into_iter()
+			.try_for_each(|message| publisher.publish(message))
+	}
+
+	fn sensors(&self) -> Vec<Sensor<'_>> {
+		const ENERGY: (Option<&str>, Option<&str>) = (Some("energy"), Some("total_increasing"));
+		const POWER: (Option<&str>, Option<&str>) = (Some("power"), Some("measurement"));
+		const VOLTAGE: (Option<&str>, Option<&str>) = (Some("voltage"), Some("measurement"));
+		const CURRENT: (Option<&str>, Option<&str>) = (Some("current"), Some("measurement"));
+		const COUNT: (Option<&str>, Option<&str>) = (None, Some("total_increasing"));
+
+		let mut out = Vec::new();
+		for (key, name, kind, value) in [
+			(
+				"electricity_consumed_tariff_1",
+				"Energy consumed tariff 1",
+				ENERGY,
+				&self.electricity_consumed_tariff_1,
+			),
+			(
+				"electricity_consumed_tariff_2",
+				"Energy consumed tariff 2",
+				ENERGY,
+				&self.electricity_consumed_tariff_2,
+			),
+			(
+				"electricity_generated_tariff_1",
+				"Energy generated tariff 1",
+				ENERGY,
+				&self.electricity_generated_tariff_1,
+			),
+			(
+				"electricity_generated_tariff_2",
+				"Energy generated tariff 2",
+				ENERGY,
+				&self.electricity_generated_tariff_2,
+			),
+			("power", "Power consumed", POWER, &self.power),
+			("return_power", "Power generated", POWER, &self.return_power),
+			("power_l1", "Power consumed L1", POWER, &self.power_l1),
+			("power_l2", "Power consumed L2", POWER, &self.power_l2),
+			("power_l3", "Power consumed L3", POWER, &self.power_l3),
+			("return_power_l1", "Power generated L1", POWER, &self.return_power_l1),
+			("return_power_l2", "Power generated L2", POWER, &self.return_power_l2),
+			("return_power_l3", "Power generated L3", POWER, &self.return_power_l3),
+			("voltage_l1", "Voltage L1", VOLTAGE, &self.voltage_l1),
+			("voltage_l2", "Voltage L2", VOLTAGE, &self.voltage_l2),
+			("voltage_l3", "Voltage L3", VOLTAGE, &self.voltage_l3),
+		] {
+			if let Some(UnitValue { value, unit }) = value {
+				out.push(Sensor::new(key, name, kind, (*value).into(), unit.as_deref()));
+			}
+		}
+		for (key, name, value) in [
+			("current_l1", "Current L1", &self.current_l1),
+			("current_l2", "Current L2", &self.current_l2),
+			("current_l3", "Current L3", &self.current_l3),
+		] {
+			if let Some(UnitValue { value, unit }) = value {
+				out.push(Sensor::new(key, name, CURRENT, (*value).into(), unit.as_deref()));
+			}
+		}
+		for (key, name, count) in [
+			("power_failure_count", "Power failures", self.power_failure_count),
+			(
+				"long_power_failure_count",
+				"Long power failures",
+				self.long_power_failure_count,
+			),
+		] {
+			if let Some(count) = count {
+				out.push(Sensor::new(key, name, COUNT, count.into(), None));
+			}
+		}
+		if let Some(tariff) = self.tariff() {
+			out.push(Sensor::new("tariff", "Tariff", (None, None), tariff.into(), None));
+		}
+		for device in &self.mbus_devices {
+			let Some(UnitValue { value, unit }) = &device.capture_value else {
+				continue;
+			};
+			// Home Assistant requires the unit for the energy sensors, so the usual one is assumed if the meter doesn't report it
+			let (device_class, name, default_unit) = match device.device_type {
+				Some(MbusDeviceType::Gas) => (Some("gas"), "Gas", None),
+				Some(MbusDeviceType::Water | MbusDeviceType::WarmWater | MbusDeviceType::HotWater | MbusDeviceType::ColdWater) => {
+					(Some("water"), "Water", None)
+				}
+				Some(MbusDeviceType::Electricity) => (Some("energy"), "Electricity", Some("kWh")),
+				Some(MbusDeviceType::Heat | MbusDeviceType::HeatInlet) => (Some("energy"), "Heat", Some("GJ")),
+				_ => (None, "M-Bus device", None),
+			};
+			out.push(Sensor::new(
+				&format!("mbus_{}", device.channel),
+				&format!("{name} {}", device.channel),
+				(device_class, Some("total_increasing")),
+				(*value).into(),
+				unit.as_deref().or(default_unit),
+			));
+		}
+		out
+	}
+}
+
+/// Value of the telegram exposed as a Home Assistant sensor
+struct Sensor<'t> {
+	key: String,
+	name: String,
+	device_class: Option<&'static str>,
+	state_class: Option<&'static str>,
+	value: Value,
+	unit: Option<&'t str>,
+}
+
+impl<'t> Sensor<'t> {
+	fn new(
+		key: &str,
+		name: &str,
+		(device_class, state_class): (Option<&'static str>, Option<&'static str>),
+		value: Value,
+		unit: Option<&'t str>,
+	) -> Self {
+		Self {
+			key: key.to_owned(),
+			name: name.to_owned(),
+			device_class,
+			state_class,
+			value,
+			unit,
+		}
+	}
+}
+
+/// Identifier usable in the MQTT topic and Home Assistant unique id
+fn node_id(id: &str) -> String {
+	id.chars()
+		.map(|c| {
+			if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+				c
+			} else {
+				'_'
+			}
+		})
+		.collect()
+}
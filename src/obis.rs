@@ -0,0 +1,191 @@
+use core::fmt;
+use core::str::{self, FromStr};
+
+/// OBIS code identifying a COSEM object, e.g. `1-0:1.8.1`
+///
+/// DSMR telegrams omit the value group F, it's set to 255 in that case. The string representation is `A-B:C.D.E`, or
+/// `A-B:C.D.E.F` if F is not 255, `A-B:C.D.E*F` is also accepted when parsing.
+///
+/// ```
+/// use dsmr_parse::ObisCode;
+///
+/// let obis: ObisCode = "1-0:1.8.1".parse().unwrap();
+/// assert_eq!(ObisCode::ELECTRICITY_CONSUMED_TARIFF_1, obis);
+/// assert_eq!("1-0:1.8.1", obis.to_string());
+/// ```
+///
+/// With the `serde` feature it's serialized as the string representation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ObisCode {
+	/// Group A: medium (0 - abstract objects, 1 - electricity, 7 - gas, etc.)
+	pub a: u8,
+	/// Group B: channel
+	pub b: u8,
+	/// Group C: physical value (current, voltage, energy, etc.)
+	pub c: u8,
+	/// Group D: processing of the physical value
+	pub d: u8,
+	/// Group E: further classification (e.g. tariff)
+	pub e: u8,
+	/// Group F: historical values, 255 if not used
+	pub f: u8,
+}
+
+impl ObisCode {
+	pub const VERSION: Self = Self::new(1, 3, 0, 2, 8, 255);
+	pub const ELECTRICITY_DATE: Self = Self::new(0, 0, 1, 0, 0, 255);
+	pub const ELECTRICITY_EQUIPMENT_ID: Self = Self::new(0, 0, 96, 1, 1, 255);
+	pub const ELECTRICITY_CONSUMED_TARIFF_1: Self = Self::new(1, 0, 1, 8, 1, 255);
+	pub const ELECTRICITY_CONSUMED_TARIFF_2: Self = Self::new(1, 0, 1, 8, 2, 255);
+	pub const ELECTRICITY_GENERATED_TARIFF_1: Self = Self::new(1, 0, 2, 8, 1, 255);
+	pub const ELECTRICITY_GENERATED_TARIFF_2: Self = Self::new(1, 0, 2, 8, 2, 255);
+	pub const CURRENT_TARIFF: Self = Self::new(0, 0, 96, 14, 0, 255);
+	pub const POWER: Self = Self::new(1, 0, 1, 7, 0, 255);
+	pub const RETURN_POWER: Self = Self::new(1, 0, 2, 7, 0, 255);
+	pub const POWER_FAILURE_COUNT: Self = Self::new(0, 0, 96, 7, 21, 255);
+	pub const LONG_POWER_FAILURE_COUNT: Self = Self::new(0, 0, 96, 7, 9, 255);
+	pub const POWER_FAILURE_LOG: Self = Self::new(1, 0, 99, 97, 0, 255);
+	/// Duration of the power failure, referenced by the entries of [ObisCode::POWER_FAILURE_LOG]
+	pub const POWER_FAILURE_DURATION: Self = Self::new(0, 0, 96, 7, 19, 255);
+	pub const VOLTAGE_SAG_L1_COUNT: Self = Self::new(1, 0, 32, 32, 0, 255);
+	pub const VOLTAGE_SAG_L2_COUNT: Self = Self::new(1, 0, 52, 32, 0, 255);
+	pub const VOLTAGE_SAG_L3_COUNT: Self = Self::new(1, 0, 72, 32, 0, 255);
+	pub const VOLTAGE_SWELL_L1_COUNT: Self = Self::new(1, 0, 32, 36, 0, 255);
+	pub const VOLTAGE_SWELL_L2_COUNT: Self = Self::new(1, 0, 52, 36, 0, 255);
+	pub const VOLTAGE_SWELL_L3_COUNT: Self = Self::new(1, 0, 72, 36, 0, 255);
+	pub const MESSAGE: Self = Self::new(0, 0, 96, 13, 0, 255);
+	pub const VOLTAGE_L1: Self = Self::new(1, 0, 32, 7, 0, 255);
+	pub const VOLTAGE_L2: Self = Self::new(1, 0, 52, 7, 0, 255);
+	pub const VOLTAGE_L3: Self = Self::new(1, 0, 72, 7, 0, 255);
+	pub const CURRENT_L1: Self = Self::new(1, 0, 31, 7, 0, 255);
+	pub const CURRENT_L2: Self = Self::new(1, 0, 51, 7, 0, 255);
+	pub const CURRENT_L3: Self = Self::new(1, 0, 71, 7, 0, 255);
+	pub const POWER_L1: Self = Self::new(1, 0, 21, 7, 0, 255);
+	pub const POWER_L2: Self = Self::new(1, 0, 41, 7, 0, 255);
+	pub const POWER_L3: Self = Self::new(1, 0, 61, 7, 0, 255);
+	pub const RETURN_POWER_L1: Self = Self::new(1, 0, 22, 7, 0, 255);
+	pub const RETURN_POWER_L2: Self = Self::new(1, 0, 42, 7, 0, 255);
+	pub const RETURN_POWER_L3: Self = Self::new(1, 0, 62, 7, 0, 255);
+	pub const EMUCS_VERSION: Self = Self::new(0, 0, 96, 1, 4, 255);
+	pub const CURRENT_AVERAGE_DEMAND: Self = Self::new(1, 0, 1, 4, 0, 255);
+	pub const MAXIMUM_DEMAND_MONTH: Self = Self::new(1, 0, 1, 6, 0, 255);
+	pub const MAXIMUM_DEMAND_HISTORY: Self = Self::new(0, 0, 98, 1, 0, 255);
+	pub const BREAKER_STATE: Self = Self::new(0, 0, 96, 3, 10, 255);
+	pub const LIMITER_THRESHOLD: Self = Self::new(0, 0, 17, 0, 0, 255);
+	pub const FUSE_SUPERVISION_THRESHOLD: Self = Self::new(1, 0, 31, 4, 0, 255);
+
+	pub const fn new(a: u8, b: u8, c: u8, d: u8, e: u8, f: u8) -> Self {
+		Self { a, b, c, d, e, f }
+	}
+
+	/// Device type of the M-Bus device on the specified channel
+	pub const fn mbus_device_type(channel: u8) -> Self {
+		Self::new(0, channel, 24, 1, 0, 255)
+	}
+
+	/// Equipment identifier of the M-Bus device on the specified channel
+	pub const fn mbus_equipment_id(channel: u8) -> Self {
+		Self::new(0, channel, 96, 1, 0, 255)
+	}
+
+	/// Valve position of the M-Bus device on the specified channel
+	pub const fn mbus_valve_position(channel: u8) -> Self {
+		Self::new(0, channel, 24, 4, 0, 255)
+	}
+
+	/// Last captured value of the M-Bus device on the specified channel
+	pub const fn mbus_capture(channel: u8) -> Self {
+		Self::new(0, channel, 24, 2, 1, 255)
+	}
+
+	/// Parse the OBIS code at the start of the telegram line, returns the code and the rest of the line
+	pub(crate) fn parse_prefix(line: &[u8]) -> Option<(Self, &[u8])> {
+		let end = line.iter().position(|&b| b == b'(').unwrap_or(line.len());
+		let obis = str::from_utf8(&line[..end]).ok()?.parse().ok()?;
+		Some((obis, &line[end..]))
+	}
+}
+
+impl FromStr for ObisCode {
+	type Err = ParseObisError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		fn group(s: Option<&str>) -> Result<u8, ParseObisError> {
+			match s {
+				Some(s) if !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit()) => s.parse().map_err(|_| ParseObisError),
+				_ => Err(ParseObisError),
+			}
+		}
+
+		let (a, rest) = s.split_once('-').ok_or(ParseObisError)?;
+		let (b, rest) = rest.split_once(':').ok_or(ParseObisError)?;
+		let (rest, star_f) = rest.split_once('*').map_or((rest, None), |(rest, f)| (rest, Some(f)));
+		let mut groups = rest.split('.');
+		let out = Self {
+			a: group(Some(a))?,
+			b: group(Some(b))?,
+			c: group(groups.next())?,
+			d: group(groups.next())?,
+			e: group(groups.next())?,
+			f: match (groups.next(), star_f) {
+				(None, None) => 255,
+				(Some(f), None) | (None, Some(f)) => group(Some(f))?,
+				(Some(_), Some(_)) => return Err(ParseObisError),
+			},
+		};
+		if groups.next().is_some() {
+			return Err(ParseObisError);
+		}
+		Ok(out)
+	}
+}
+
+impl fmt::Display for ObisCode {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}-{}:{}.{}.{}", self.a, self.b, self.c, self.d, self.e)?;
+		if self.f != 255 {
+			write!(f, ".{}", self.f)?;
+		}
+		Ok(())
+	}
+}
+
+/// Error returned when parsing an invalid [ObisCode] string
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseObisError;
+
+impl fmt::Display for ParseObisError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str("Invalid OBIS code")
+	}
+}
+
+impl core::error::Error for ParseObisError {}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for ObisCode {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_str(self)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ObisCode {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		struct Visitor;
+
+		impl serde::de::Visitor<'_> for Visitor {
+			type Value = ObisCode;
+
+			fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+				f.write_str("OBIS code like 1-0:1.8.1")
+			}
+
+			fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+				v.parse().map_err(E::custom)
+			}
+		}
+
+		deserializer.deserialize_str(Visitor)
+	}
+}
@@ -0,0 +1,282 @@
+use alloc::string::String;
+use core::fmt;
+use core::fmt::Write;
+
+use crate::{Telegram, UnitValue};
+
+impl Telegram {
+	/// Render the telegram in the OpenMetrics text format for Prometheus
+	///
+	/// The cumulative registers (energy per tariff, M-Bus device readings like the gas volume) and the power failure,
+	/// voltage sag and swell counts are exposed as counters with the `_total` suffix, the instantaneous values (power,
+	/// voltage, current) as gauges. The unit is a part of the metric name, the values reported by the meter with a different
+	/// prefix (e.g. `Wh` or `W`) are converted to it, and the ones in an unrelated unit are skipped. The values without the
+	/// unit are assumed to be in the unit of the metric. All metrics are prefixed with `dsmr_` and labelled with the `equipment_id` of the meter if it's known, the
+	/// per-tariff and per-phase ones additionally get the `tariff` and `phase` labels. M-Bus readings are labelled with
+	/// the channel, device type code, equipment id and unit of the device. The output ends with `# EOF`.
+	///
+	/// ```
+	/// use dsmr_parse::Telegram;
+	///
+	/// let telegram =
+	///     Telegram::read_from(b"/XMX5LGBBFG1009394887\r\n\r\n1-3:0.2.8(50)\r\n1-0:1.8.1(004169.415*kWh)\r\n!47AD\r\n".as_slice())
+	///         .unwrap()
+	///         .unwrap();
+	/// let metrics = telegram.to_openmetrics();
+	/// assert!(metrics.contains("\ndsmr_electricity_consumed_kilowatt_hours_total{tariff=\"1\"} 4169.415\n"));
+	/// ```
+	pub fn to_openmetrics(&self) -> String {
+		let mut out = MetricsWriter {
+			out: String::with_capacity(4096),
+			name: String::new(),
+			header: None,
+		};
+		// writing to a String can't fail
+		let _ = self.write_openmetrics(&mut out);
+		out.out.push_str("# EOF\n");
+		out.out
+	}
+
+	fn write_openmetrics(&self, out: &mut MetricsWriter) -> fmt::Result {
+		let meter: &[(&str, &dyn fmt::Display)] = match &self.electricity_equipment_id {
+			Some(id) => &[("equipment_id", id)],
+			None => &[],
+		};
+
+		out.family("dsmr_meter", "info", None, "Meter identification")?;
+		let version: &dyn fmt::Display = match &self.version {
+			Some(version) => version,
+			None => &"",
+		};
+		out.sample("_info", meter, &[("ident", &self.ident), ("version", version)], 1)?;
+
+		for (name, help, tariff_1, tariff_2) in [
+			(
+				"dsmr_electricity_consumed_kilowatt_hours",
+				"Electricity delivered to the client",
+				&self.electricity_consumed_tariff_1,
+				&self.electricity_consumed_tariff_2,
+			),
+			(
+				"dsmr_electricity_generated_kilowatt_hours",
+				"Electricity delivered by the client",
+				&self.electricity_generated_tariff_1,
+				&self.electricity_generated_tariff_2,
+			),
+		] {
+			out.family(name, "counter", Some("kilowatt_hours"), help)?;
+			for (tariff, value) in [("1", tariff_1), ("2", tariff_2)] {
+				if let Some(value) = in_unit(value, "kWh") {
+					out.sample("_total", meter, &[("tariff", &tariff)], value)?;
+				}
+			}
+		}
+		if let Some(tariff) = self.tariff() {
+			out.family("dsmr_tariff", "gauge", None, "Current tariff indicator")?;
+			out.sample("", meter, &[], tariff)?;
+		}
+
+		for (name, phase_name, help, total, l1, l2, l3) in [
+			(
+				"dsmr_power_consumed_kilowatts",
+				"dsmr_phase_power_consumed_kilowatts",
+				"Actual power delivered to the client",
+				&self.power,
+				&self.power_l1,
+				&self.power_l2,
+				&self.power_l3,
+			),
+			(
+				"dsmr_power_generated_kilowatts",
+				"dsmr_phase_power_generated_kilowatts",
+				"Actual power delivered by the client",
+				&self.return_power,
+				&self.return_power_l1,
+				&self.return_power_l2,
+				&self.return_power_l3,
+			),
+		] {
+			out.family(name, "gauge", Some("kilowatts"), help)?;
+			if let Some(value) = in_unit(total, "kW") {
+				out.sample("", meter, &[], value)?;
+			}
+			// separate family, so that the phases can be summed up without the total
+			out.family(phase_name, "gauge", Some("kilowatts"), help)?;
+			for (phase, value) in [("l1", l1), ("l2", l2), ("l3", l3)] {
+				if let Some(value) = in_unit(value, "kW") {
+					out.sample("", meter, &[("phase", &phase)], value)?;
+				}
+			}
+		}
+		out.family("dsmr_voltage_volts", "gauge", Some("volts"), "Instantaneous voltage")?;
+		for (phase, value) in [("l1", &self.voltage_l1), ("l2", &self.voltage_l2), ("l3", &self.voltage_l3)] {
+			if let Some(value) = in_unit(value, "V") {
+				out.sample("", meter, &[("phase", &phase)], value)?;
+			}
+		}
+		out.family("dsmr_current_amperes", "gauge", Some("amperes"), "Instantaneous current")?;
+		for (phase, value) in [("l1", &self.current_l1), ("l2", &self.current_l2), ("l3", &self.current_l3)] {
+			if let Some(value) = in_unit(value, "A") {
+				out.sample("", meter, &[("phase", &phase)], value)?;
+			}
+		}
+
+		for (name, help, count) in [
+			("dsmr_power_failures", "Number of power failures", self.power_failure_count),
+			(
+				"dsmr_long_power_failures",
+				"Number of long power failures",
+				self.long_power_failure_count,
+			),
+		] {
+			if let Some(count) = count {
+				out.family(name, "counter", None, help)?;
+				out.sample("_total", meter, &[], count)?;
+			}
+		}
+		for (name, help, l1, l2, l3) in [
+			(
+				"dsmr_voltage_sags",
+				"Number of voltage sags",
+				self.voltage_sag_l1_count,
+				self.voltage_sag_l2_count,
+				self.voltage_sag_l3_count,
+			),
+			(
+				"dsmr_voltage_swells",
+				"Number of voltage swells",
+				self.voltage_swell_l1_count,
+				self.voltage_swell_l2_count,
+				self.voltage_swell_l3_count,
+			),
+		] {
+			out.family(name, "counter", None, help)?;
+			for (phase, count) in [("l1", l1), ("l2", l2), ("l3", l3)] {
+				if let Some(count) = count {
+					out.sample("_total", meter, &[("phase", &phase)], count)?;
+				}
+			}
+		}
+
+		out.family(
+			"dsmr_mbus_reading",
+			"counter",
+			None,
+			"Last value captured from the M-Bus device",
+		)?;
+		for device in &self.mbus_devices {
+			let Some(UnitValue { value, unit }) = &device.capture_value else {
+				continue;
+			};
+			let device_type: &dyn fmt::Display = match &device.device_type {
+				Some(device_type) => &u8::from(*device_type),
+				None => &"",
+			};
+			let equipment_id: &dyn fmt::Display = match &device.equipment_id {
+				Some(id) => id,
+				None => &"",
+			};
+			let unit: &dyn fmt::Display = match unit {
+				Some(unit) => unit,
+				None => &"",
+			};
+			let labels: [(&str, &dyn fmt::Display); 4] = [
+				("channel", &device.channel),
+				("device_type", device_type),
+				("equipment_id", equipment_id),
+				("unit", unit),
+			];
+			out.sample("_total", &[], &labels, value)?;
+		}
+		Ok(())
+	}
+}
+
+/// SI prefixes of the units reported by the meters along with their scale
+const PREFIXES: [(&str, f64); 4] = [("", 1.0), ("k", 1e3), ("M", 1e6), ("m", 1e-3)];
+
+/// Value converted to the unit of the metric (e.g. `Wh` to `kWh`), `None` if it's missing or in an unrelated unit
+fn in_unit<T: Copy + Into<f64>>(value: &Option<UnitValue<T>>, metric_unit: &str) -> Option<f64> {
+	let UnitValue { value, unit } = value.as_ref()?;
+	let value = (*value).into();
+	let unit = match unit.as_deref() {
+		None => return Some(value),
+		Some(unit) if unit == metric_unit => return Some(value),
+		Some(unit) => unit,
+	};
+	let base = metric_unit.strip_prefix('k').unwrap_or(metric_unit);
+	let scale = |unit: &str| {
+		PREFIXES
+			.iter()
+			.find_map(|(prefix, scale)| (unit.strip_prefix(prefix) == Some(base)).then_some(*scale))
+	};
+	Some(value * scale(unit)? / scale(metric_unit)?)
+}
+
+/// Accumulates the metric families, the header of a family is written only if it gets any samples
+struct MetricsWriter {
+	out: String,
+	/// Name of the current family
+	name: String,
+	/// Header of the current family if it has no samples yet
+	header: Option<String>,
+}
+
+impl MetricsWriter {
+	fn family(&mut self, name: &str, metric_type: &str, unit: Option<&str>, help: &str) -> fmt::Result {
+		let mut header = String::new();
+		writeln!(header, "# TYPE {name} {metric_type}")?;
+		if let Some(unit) = unit {
+			writeln!(header, "# UNIT {name} {unit}")?;
+		}
+		writeln!(header, "# HELP {name} {help}")?;
+		self.name.clear();
+		self.name.push_str(name);
+		self.header = Some(header);
+		Ok(())
+	}
+
+	fn sample(
+		&mut self,
+		suffix: &str,
+		common_labels: &[(&str, &dyn fmt::Display)],
+		labels: &[(&str, &dyn fmt::Display)],
+		value: impl fmt::Display,
+	) -> fmt::Result {
+		if let Some(header) = self.header.take() {
+			self.out.push_str(&header);
+		}
+		write!(self.out, "{}{suffix}", self.name)?;
+		for (i, (key, value)) in common_labels.iter().chain(labels).enumerate() {
+			let separator = if i == 0 {
+				'{'
+			} else {
+				','
+			};
+			write!(self.out, "{separator}{key}=\"{}\"", LabelValue(*value))?;
+		}
+		if !common_labels.is_empty() || !labels.is_empty() {
+			self.out.push('}');
+		}
+		writeln!(self.out, " {value}")
+	}
+}
+
+/// Label value with the backslash, double quote and line feed escaped
+struct LabelValue<'v>(&'v dyn fmt::Display);
+
+impl fmt::Display for LabelValue<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let mut value = String::new();
+		write!(value, "{}", self.0)?;
+		for c in value.chars() {
+			match c {
+				'\\' => f.write_str("\\\\")?,
+				'"' => f.write_str("\\\"")?,
+				'\n' => f.write_str("\\n")?,
+				c => f.write_char(c)?,
+			}
+		}
+		Ok(())
+	}
+}
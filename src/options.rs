@@ -0,0 +1,41 @@
+/// Options controlling how strictly the telegrams are parsed
+///
+/// Pass them to the `with_options` constructor of any reader, e.g. [TelegramDecoder::with_options](crate::TelegramDecoder::with_options).
+///
+/// ```
+/// use dsmr_parse::{ParseOptions, TelegramDecoder};
+///
+/// let mut decoder = TelegramDecoder::with_options(ParseOptions {
+///     lenient: true,
+///     ..ParseOptions::default()
+/// });
+/// let telegrams = decoder.feed(b"/XMX5LGBBFG1009394887\r\n\r\n1-3:0.2.8(42)\r\n1-0:31.7.0(4.5*A)\r\n!A68E\r\n");
+/// let telegram = telegrams[0].as_ref().unwrap();
+/// assert_eq!(None, telegram.current_l1);
+/// assert_eq!("1-0:31.7.0", telegram.field_errors[0].obis.to_string());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ParseOptions {
+	/// Don't fail the whole telegram when the value of a known object can't be decoded
+	///
+	/// The corresponding field is left unset and the problem is recorded in [Telegram::field_errors](crate::Telegram::field_errors)
+	/// instead, along with the malformed object lines that are otherwise only logged and skipped. The CRC is still checked.
+	pub lenient: bool,
+	/// How to treat the telegrams with invalid CRC
+	pub crc: CrcMode,
+}
+
+/// Handling of the telegram CRC
+///
+/// The CRC check result is available in [Telegram::crc_valid](crate::Telegram::crc_valid), along with the computed and
+/// the received CRC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CrcMode {
+	/// Reject the telegrams with invalid CRC with [Error::CrcMismatch](crate::Error::CrcMismatch)
+	#[default]
+	Strict,
+	/// Accept the telegrams with invalid CRC logging a warning
+	Accept,
+	/// Accept the telegrams with invalid CRC silently
+	Ignore,
+}
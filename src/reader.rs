@@ -0,0 +1,82 @@
+use std::io::BufRead;
+
+use crate::line_reader::LineReader;
+use crate::{Error, ParseOptions, Telegram, TelegramParser};
+
+/// Continuous reader of telegrams from a [BufRead] source
+///
+/// Meters send a new telegram periodically (usually every second), this reader owns the source and yields telegrams one
+/// after another. A telegram that fails to parse (e.g. because of the CRC mismatch or a garbled line) is yielded as an
+/// error, after which the reader skips the data until the next telegram header and continues from there. IO errors are
+/// yielded as is, the iteration ends only when the source is exhausted. If it's exhausted in the middle of a telegram, the
+/// last item is [Error::TruncatedTelegram].
+///
+/// The source is never read past the end of the last yielded telegram, so it can be taken back with
+/// [TelegramReader::into_inner] without losing any data.
+///
+/// ```
+/// use dsmr_parse::TelegramReader;
+///
+/// let data = b"/XMX5LGBBFG1009394887\r\n\r\n1-3:0.2.8(42)\r\n!0D7D\r\n/XMX5LGBBFG1009394887\r\n\r\n1-3:0.2.8(42)\r\n!0D7D\r\n";
+///
+/// for telegram in TelegramReader::new(data.as_slice()) {
+///     match telegram {
+///         Ok(telegram) => println!("Read telegram: {telegram:?}"),
+///         Err(e) => eprintln!("Parse error: {e}"),
+///     }
+/// }
+/// ```
+pub struct TelegramReader<R> {
+	lines: LineReader<R>,
+	parser: TelegramParser,
+}
+
+impl<R: BufRead> TelegramReader<R> {
+	pub fn new(src: R) -> Self {
+		Self::with_options(src, ParseOptions::default())
+	}
+
+	pub fn with_options(src: R, options: ParseOptions) -> Self {
+		Self {
+			lines: LineReader::new(src),
+			parser: TelegramParser::new(options),
+		}
+	}
+
+	pub fn get_ref(&self) -> &R {
+		self.lines.get_ref()
+	}
+
+	pub fn get_mut(&mut self) -> &mut R {
+		self.lines.get_mut()
+	}
+
+	/// Number of bytes skipped before the header of the last telegram, assuming CRLF line terminators
+	pub fn skipped_bytes(&self) -> usize {
+		self.parser.skipped_bytes()
+	}
+
+	/// Return the underlying source, the incomplete line that is read but not yet parsed is lost
+	pub fn into_inner(self) -> R {
+		self.lines.into_inner()
+	}
+}
+
+impl<R: BufRead> Iterator for TelegramReader<R> {
+	type Item = Result<Telegram, Error>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		loop {
+			let Some(line) = self.lines.read_line() else {
+				return self.parser.finish();
+			};
+			let res = match line {
+				Ok(line) => self.parser.push_line(line),
+				Err(e) => Some(Err(Error::Io(e))),
+			};
+			if res.is_some() {
+				return res;
+			}
+		}
+	}
+}
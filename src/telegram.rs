@@ -1,18 +1,48 @@
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
 use core::str;
-use std::io::Read;
-use std::str::FromStr;
+use core::str::FromStr;
+#[cfg(feature = "std")]
+use std::io::BufRead;
 
-pub use error::Error;
-use log::{trace, warn};
+pub use borrowed::{CosemObjectRef, TelegramRef};
+pub use error::{Error, LineError};
+use log::{debug, warn};
+pub use mbus::{MbusDevice, MbusDeviceType};
+pub(crate) use parser::TelegramParser;
+#[cfg(any(
+	feature = "csv",
+	feature = "json",
+	feature = "influx",
+	feature = "mqtt",
+	feature = "openmetrics"
+))]
+pub(crate) use writer::OctetString;
 
-use crate::Tst;
-use crate::line_reader::LineReader;
 use crate::unit_value::UnitValue;
+use crate::{CrcMode, ObisCode, Tst};
 
+mod borrowed;
 mod error;
+mod mbus;
+mod parser;
+mod writer;
+
+const CRLF: &[u8] = b"\r\n";
+const CRC_PREFIX: &[u8] = b"!";
+/// Maximum length of a telegram line, longer lines are rejected with [Error::LineTooLong]
+///
+/// The longest standard object is the text message (`0-0:96.13.0`) of up to 1024 octets, which is 2048 characters
+/// hex-encoded.
+pub const MAX_LINE_LEN: usize = 4096;
 
 /// Valid telegram
+///
+/// With the `serde` feature enabled it implements `Serialize` and `Deserialize`, the octet string fields like
+/// [Telegram::electricity_equipment_id] and [Telegram::message] are serialized as the decoded text.
 #[derive(Debug, PartialOrd, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Telegram {
 	pub ident: String,
 	pub version: Option<String>,
@@ -47,150 +77,293 @@ pub struct Telegram {
 	pub return_power_l1: Option<UnitValue<f64>>,
 	pub return_power_l2: Option<UnitValue<f64>>,
 	pub return_power_l3: Option<UnitValue<f64>>,
-	pub device_type: Option<String>,
-	pub gas_equipment_id: Option<String>,
-	pub gas_date: Option<Tst>,
-	pub gas_consumed: Option<UnitValue<f64>>,
+	/// Version of the Belgian e-MUCS specification
+	pub emucs_version: Option<String>,
+	/// Average demand in the current quarter-hour (e-MUCS)
+	pub current_average_demand: Option<UnitValue<f64>>,
+	/// Maximum quarter-hour average demand in the current month (e-MUCS)
+	pub maximum_demand_month: Option<DemandPeak>,
+	/// Maximum demand of the previous months, up to 13 entries (e-MUCS)
+	pub maximum_demand_history: Vec<MaximumDemandEntry>,
+	/// Breaker state: 0 - disconnected, 1 - connected, 2 - ready for connection
+	pub breaker_state: Option<u8>,
+	/// Threshold of the power limiter (e-MUCS, DSMR 2.2)
+	pub limiter_threshold: Option<UnitValue<f64>>,
+	/// Fuse supervision threshold on L1 (e-MUCS)
+	pub fuse_supervision_threshold: Option<UnitValue<u16>>,
+	/// Devices (gas, water, heat meters, etc.) connected over M-Bus, in the order of appearance in the telegram
+	pub mbus_devices: Vec<MbusDevice>,
+	/// All COSEM objects of the telegram in the order of appearance, including the ones not decoded into the fields above
+	pub objects: Vec<CosemObject>,
+	/// Objects whose values couldn't be decoded, only collected with [ParseOptions::lenient](crate::ParseOptions::lenient)
+	pub field_errors: Vec<FieldError>,
+	/// Whether the CRC of the telegram matched, can be `false` only with [CrcMode::Accept] or [CrcMode::Ignore]
+	///
+	/// Legacy telegrams without CRC are considered valid.
+	pub crc_valid: bool,
+	/// CRC computed over the received telegram
+	pub crc_actual: u16,
+	/// CRC from the CRC line of the telegram, `None` if it's missing or not a valid hex number
+	pub crc_expected: Option<u16>,
 }
 
 impl Telegram {
-	/// Try to read a single telegram from a [Read] source
+	/// Try to read a single telegram from a [BufRead] source
+	///
+	/// The source is read only up to the end of the telegram, the following data stays in its buffer. Pass it by mutable
+	/// reference to continue reading from it afterward.
+	///
+	/// Returns `Ok(None)` if the source is exhausted before any telegram header, and [Error::TruncatedTelegram] if it's
+	/// exhausted in the middle of a telegram.
 	///
 	/// See [crate-level](crate) documentation for more details.
-	pub fn read_from(src: impl Read) -> Result<Option<Self>, Error> {
-		enum ParserState {
-			WaitingForHeader,
-			ReadingHeader,
-			ReadingMessage,
+	#[cfg(feature = "std")]
+	pub fn read_from(src: impl BufRead) -> Result<Option<Self>, Error> {
+		crate::TelegramReader::new(src).next().transpose()
+	}
+
+	/// Find the first object with the matching OBIS code
+	///
+	/// ```
+	/// use dsmr_parse::{ObisCode, TelegramDecoder};
+	///
+	/// let mut decoder = TelegramDecoder::new();
+	/// let telegrams = decoder.feed(b"/XMX5LGBBFG1009394887\r\n\r\n1-3:0.2.8(42)\r\n1-0:1.8.1(004169.415*kWh)\r\n!9FC1\r\n");
+	/// let telegram = telegrams[0].as_ref().unwrap();
+	/// let consumed = telegram.get(ObisCode::ELECTRICITY_CONSUMED_TARIFF_1).unwrap();
+	/// assert_eq!(["004169.415*kWh"], consumed.values.as_slice());
+	/// ```
+	pub fn get(&self, obis: ObisCode) -> Option<&CosemObject> {
+		self.objects.iter().find(|object| object.obis == obis)
+	}
+
+	/// Whether the decoded fields of the telegrams are equal, ignoring the data filled in by the parser: the raw
+	/// [objects](Telegram::objects), the [field errors](Telegram::field_errors) and the CRC
+	pub fn fields_eq(&self, other: &Telegram) -> bool {
+		let fields = |telegram: &Telegram| Telegram {
+			objects: vec![],
+			field_errors: vec![],
+			crc_valid: false,
+			crc_actual: 0,
+			crc_expected: None,
+			..telegram.clone()
+		};
+		fields(self) == fields(other)
+	}
+
+	/// M-Bus device connected to the specified channel (1..=4)
+	pub fn mbus_device(&self, channel: u8) -> Option<&MbusDevice> {
+		self.mbus_devices.iter().find(|device| device.channel == channel)
+	}
+
+	/// First M-Bus device of the specified type
+	pub fn mbus_device_by_type(&self, device_type: MbusDeviceType) -> Option<&MbusDevice> {
+		self
+			.mbus_devices
+			.iter()
+			.find(|device| device.device_type == Some(device_type))
+	}
+
+	/// Current tariff indicator as a number, e.g. `1` for `0-0:96.14.0(0001)`
+	///
+	/// Taken from the raw object if the telegram has it, otherwise from [Telegram::current_tariff].
+	#[cfg(any(feature = "json", feature = "influx", feature = "mqtt", feature = "openmetrics"))]
+	pub(crate) fn tariff(&self) -> Option<u16> {
+		match self.get(ObisCode::CURRENT_TARIFF) {
+			Some(object) => parse_tariff(object.values.first()?),
+			None => parse_tariff(&OctetString(self.current_tariff.as_ref()?).to_string()),
 		}
+	}
 
-		const CRLF: &[u8] = b"\r\n";
-		let mut parser_state = ParserState::WaitingForHeader;
-		let mut out = Self::default();
-		let mut crc = crc16::State::<crc16::ARC>::new();
-		let mut expected_crc = None;
-		#[expect(clippy::unbuffered_bytes)]
-		let lines = LineReader::new(src.bytes());
-		for line in lines {
-			let mut line = line?;
-			trace!("Got line: {}", String::from_utf8_lossy(&line));
-			match parser_state {
-				ParserState::WaitingForHeader => {
-					if line.starts_with(b"/") {
-						crc.update(&line);
-						crc.update(CRLF);
-						line.remove(0);
-						out.ident = String::from_utf8(line)?;
-						parser_state = ParserState::ReadingHeader;
-					}
-				}
-				ParserState::ReadingHeader => {
-					if line.is_empty() {
-						crc.update(CRLF);
-						parser_state = ParserState::ReadingMessage;
-					} else {
-						parser_state = ParserState::WaitingForHeader;
-					}
-				}
-				ParserState::ReadingMessage => {
-					const CRC_PREFIX: &[u8] = b"!";
-					if let Some(crc_str) = line.strip_prefix(CRC_PREFIX) {
-						crc.update(CRC_PREFIX);
-						expected_crc = Some(u16::from_str_radix(str::from_utf8(crc_str)?, 16)?);
-						break;
-					} else {
-						crc.update(&line);
-						crc.update(CRLF);
-						if let Some(line) = ParsedLine::parse(&line) {
-							match line.obis.obis {
-								b"0.2.8" => out.version = Some(line.value_str().to_string()),
-								b"1.0.0" => out.electricity_date = Tst::try_from_bytes(line.value),
-								b"96.1.1" => out.electricity_equipment_id = parse_octet_string(line.value),
-								b"1.8.1" => out.electricity_consumed_tariff_1 = Some(line.value_str().parse()?),
-								b"1.8.2" => out.electricity_consumed_tariff_2 = Some(line.value_str().parse()?),
-								b"2.8.1" => out.electricity_generated_tariff_1 = Some(line.value_str().parse()?),
-								b"2.8.2" => out.electricity_generated_tariff_2 = Some(line.value_str().parse()?),
-								b"96.14.0" => out.current_tariff = parse_octet_string(line.value),
-								b"1.7.0" => out.power = Some(line.value_str().parse()?),
-								b"2.7.0" => out.return_power = Some(line.value_str().parse()?),
-								b"96.7.21" => out.power_failure_count = Some(line.value_str().parse()?),
-								b"96.7.9" => out.long_power_failure_count = Some(line.value_str().parse()?),
-								b"99.97.0" => {
-									out.power_failure_log = parse_buffer(line.value_str())
-										.into_iter()
-										.map(|(end_date, duration)| PowerFailureEntry { end_date, duration })
-										.collect()
-								}
-								b"32.32.0" => out.voltage_sag_l1_count = Some(line.value_str().parse()?),
-								b"52.32.0" => out.voltage_sag_l2_count = Some(line.value_str().parse()?),
-								b"72.32.0" => out.voltage_sag_l3_count = Some(line.value_str().parse()?),
-								b"32.36.0" => out.voltage_swell_l1_count = Some(line.value_str().parse()?),
-								b"52.36.0" => out.voltage_swell_l2_count = Some(line.value_str().parse()?),
-								b"72.36.0" => out.voltage_swell_l3_count = Some(line.value_str().parse()?),
-								b"96.13.0" => out.message = parse_octet_string(line.value),
-								b"32.7.0" => out.voltage_l1 = Some(line.value_str().parse()?),
-								b"52.7.0" => out.voltage_l2 = Some(line.value_str().parse()?),
-								b"72.7.0" => out.voltage_l3 = Some(line.value_str().parse()?),
-								b"31.7.0" => out.current_l1 = Some(line.value_str().parse()?),
-								b"51.7.0" => out.current_l2 = Some(line.value_str().parse()?),
-								b"71.7.0" => out.current_l3 = Some(line.value_str().parse()?),
-								b"21.7.0" => out.power_l1 = Some(line.value_str().parse()?),
-								b"41.7.0" => out.power_l2 = Some(line.value_str().parse()?),
-								b"61.7.0" => out.power_l3 = Some(line.value_str().parse()?),
-								b"22.7.0" => out.return_power_l1 = Some(line.value_str().parse()?),
-								b"42.7.0" => out.return_power_l2 = Some(line.value_str().parse()?),
-								b"62.7.0" => out.return_power_l3 = Some(line.value_str().parse()?),
-								b"24.1.0" => out.device_type = Some(line.value_str().to_string()),
-								b"96.1.0" => out.gas_equipment_id = parse_octet_string(line.value),
-								b"24.2.1" => {
-									let (gas_date, gas_consumed) = parse_mbus_value(line.value_str());
-									out.gas_date = Tst::try_from_bytes(gas_date.as_bytes());
-									if let Some(gas_consumed) = gas_consumed {
-										out.gas_consumed = Some(gas_consumed.parse()?);
-									}
-								}
-								_ => warn!(
-									"Unknown OBIS: {}-{}-{} with value: {}",
-									line.obis.medium,
-									line.obis.channel,
-									String::from_utf8_lossy(line.obis.obis),
-									String::from_utf8_lossy(line.value),
-								),
-							}
-						}
-					}
-				}
-			}
+	/// Parse the object line and update the corresponding field, the value that can't be decoded is recorded in
+	/// [Telegram::field_errors] as in the lenient mode
+	#[cfg(feature = "csv")]
+	pub(crate) fn apply_raw_line(&mut self, line: &[u8]) -> Result<(), Error> {
+		let line = ParsedLine::parse(line)?;
+		if let Err(e) = self.apply_line(&line) {
+			self.field_errors.push(FieldError::new(&line, &e));
 		}
+		Ok(())
+	}
+
+	/// Record the object from the parsed line and update the corresponding field, returns `false` if the object isn't
+	/// decoded into any field
+	fn apply_line(&mut self, line: &ParsedLine) -> Result<bool, Error> {
+		let decoded = self.decode_line(line);
+		self.objects.push(CosemObject {
+			obis: line.obis,
+			values: line
+				.values
+				.iter()
+				.map(|value| String::from_utf8_lossy(value).into_owned())
+				.collect(),
+			decoded: matches!(decoded, Ok(true)),
+		});
+		decoded
+	}
 
-		if let Some(expected_crc) = expected_crc {
-			let actual_crc = crc.get();
-			if actual_crc == expected_crc {
-				Ok(Some(out))
+	fn decode_line(&mut self, line: &ParsedLine) -> Result<bool, Error> {
+		if line.obis.a == 0 && MbusDevice::CHANNELS.contains(&line.obis.b) {
+			let device = if let Some(pos) = self.mbus_devices.iter().position(|d| d.channel == line.obis.b) {
+				&mut self.mbus_devices[pos]
 			} else {
-				Err(Error::CrcMismatch(actual_crc, expected_crc))
+				self.mbus_devices.push(MbusDevice::new(line.obis.b));
+				self.mbus_devices.last_mut().expect("Just pushed")
+			};
+			return device.apply_line(line);
+		}
+		match (line.obis.c, line.obis.d, line.obis.e) {
+			(0, 2, 8) => self.version = Some(line.value_str().to_string()),
+			(1, 0, 0) => self.electricity_date = Tst::try_from_bytes(line.value()),
+			(96, 1, 1) => self.electricity_equipment_id = parse_octet_string(line.value()),
+			(1, 8, 1) => self.electricity_consumed_tariff_1 = Some(line.value_str().parse()?),
+			(1, 8, 2) => self.electricity_consumed_tariff_2 = Some(line.value_str().parse()?),
+			(2, 8, 1) => self.electricity_generated_tariff_1 = Some(line.value_str().parse()?),
+			(2, 8, 2) => self.electricity_generated_tariff_2 = Some(line.value_str().parse()?),
+			(96, 14, 0) => self.current_tariff = parse_octet_string(line.value()),
+			(1, 7, 0) => self.power = Some(line.value_str().parse()?),
+			(2, 7, 0) => self.return_power = Some(line.value_str().parse()?),
+			(96, 7, 21) => self.power_failure_count = Some(line.value_str().parse()?),
+			(96, 7, 9) => self.long_power_failure_count = Some(line.value_str().parse()?),
+			(99, 97, 0) => {
+				self.power_failure_log = parse_buffer(line.value_strs())
+					.into_iter()
+					.map(|(end_date, duration)| PowerFailureEntry { end_date, duration })
+					.collect()
+			}
+			(32, 32, 0) => self.voltage_sag_l1_count = Some(line.value_str().parse()?),
+			(52, 32, 0) => self.voltage_sag_l2_count = Some(line.value_str().parse()?),
+			(72, 32, 0) => self.voltage_sag_l3_count = Some(line.value_str().parse()?),
+			(32, 36, 0) => self.voltage_swell_l1_count = Some(line.value_str().parse()?),
+			(52, 36, 0) => self.voltage_swell_l2_count = Some(line.value_str().parse()?),
+			(72, 36, 0) => self.voltage_swell_l3_count = Some(line.value_str().parse()?),
+			(96, 13, 0) => self.message = parse_octet_string(line.value()),
+			(32, 7, 0) => self.voltage_l1 = Some(line.value_str().parse()?),
+			(52, 7, 0) => self.voltage_l2 = Some(line.value_str().parse()?),
+			(72, 7, 0) => self.voltage_l3 = Some(line.value_str().parse()?),
+			(31, 7, 0) => self.current_l1 = Some(line.value_str().parse()?),
+			(51, 7, 0) => self.current_l2 = Some(line.value_str().parse()?),
+			(71, 7, 0) => self.current_l3 = Some(line.value_str().parse()?),
+			(21, 7, 0) => self.power_l1 = Some(line.value_str().parse()?),
+			(41, 7, 0) => self.power_l2 = Some(line.value_str().parse()?),
+			(61, 7, 0) => self.power_l3 = Some(line.value_str().parse()?),
+			(22, 7, 0) => self.return_power_l1 = Some(line.value_str().parse()?),
+			(42, 7, 0) => self.return_power_l2 = Some(line.value_str().parse()?),
+			(62, 7, 0) => self.return_power_l3 = Some(line.value_str().parse()?),
+			(96, 1, 4) => self.emucs_version = Some(line.value_str().to_string()),
+			(1, 4, 0) => self.current_average_demand = Some(line.value_str().parse()?),
+			(1, 6, 0) => {
+				if let (Some(date), Some(demand)) = (Tst::try_from_bytes(line.value()), line.value_strs().nth(1)) {
+					self.maximum_demand_month = Some(DemandPeak {
+						date,
+						demand: demand.parse()?,
+					});
+				}
+			}
+			(98, 1, 0) => self.maximum_demand_history = parse_demand_history(line.value_strs()),
+			(96, 3, 10) => self.breaker_state = Some(line.value_str().parse()?),
+			(17, 0, 0) => self.limiter_threshold = Some(line.value_str().parse()?),
+			(31, 4, 0) => self.fuse_supervision_threshold = Some(line.value_str().parse()?),
+			_ => {
+				line.log_unknown();
+				return Ok(false);
 			}
-		} else {
-			Ok(None)
 		}
+		Ok(true)
 	}
 }
 
+/// Raw COSEM object of the telegram
+#[derive(Debug, PartialOrd, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CosemObject {
+	pub obis: ObisCode,
+	/// Raw values of the object, one per parenthesized group
+	pub values: Vec<String>,
+	/// Whether the object was decoded into a field of the [Telegram], the other objects are written by its `Display` as is
+	#[cfg_attr(feature = "serde", serde(default))]
+	pub decoded: bool,
+}
+
+/// Object of the telegram that failed to decode into the corresponding field
 #[derive(Debug, PartialOrd, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FieldError {
+	pub obis: ObisCode,
+	/// Raw values of the object, one per parenthesized group, or the whole text after the OBIS code if the line is malformed
+	pub values: Vec<String>,
+	/// Description of the decoding error
+	pub message: String,
+}
+
+impl FieldError {
+	fn new(line: &ParsedLine, error: &Error) -> Self {
+		Self {
+			obis: line.obis,
+			values: line
+				.values
+				.iter()
+				.map(|value| String::from_utf8_lossy(value).into_owned())
+				.collect(),
+			message: error.to_string(),
+		}
+	}
+}
+
+#[derive(Debug, PartialOrd, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PowerFailureEntry {
 	pub end_date: Tst,
 	pub duration: UnitValue<u64>,
 }
 
-fn parse_mbus_value(value: &str) -> (&str, Option<&str>) {
-	value
-		.split_once(")(")
-		.map_or((value, None), |(val1, val2)| (val1, Some(val2)))
+/// Check the CRC line of the telegram, `optional` allows the bare `!` without CRC for the legacy telegrams, see
+/// [LineFramer](parser::LineFramer)
+///
+/// Returns whether the CRC is valid and the CRC from the line. Invalid CRC is an error only with [CrcMode::Strict].
+fn check_crc(actual_crc: u16, crc_str: &[u8], optional: bool, mode: CrcMode) -> Result<(bool, Option<u16>), Error> {
+	if optional && crc_str.is_empty() {
+		return Ok((true, None));
+	}
+	let expected_crc = str::from_utf8(crc_str)
+		.map_err(Error::from)
+		.and_then(|crc_str| Ok(u16::from_str_radix(crc_str, 16)?));
+	match (expected_crc, mode) {
+		(Ok(expected_crc), _) if actual_crc == expected_crc => Ok((true, Some(expected_crc))),
+		(Ok(expected_crc), CrcMode::Strict) => Err(Error::CrcMismatch(actual_crc, expected_crc)),
+		(Err(e), CrcMode::Strict) => Err(e),
+		(expected_crc, mode) => {
+			if mode == CrcMode::Accept {
+				warn!(
+					"Accepting telegram with invalid CRC, computed: {actual_crc:X}, expected: {}",
+					String::from_utf8_lossy(crc_str)
+				);
+			}
+			Ok((false, expected_crc.ok()))
+		}
+	}
 }
 
-fn parse_buffer(value: &str) -> Vec<(Tst, UnitValue<u64>)> {
+/// Peak of the quarter-hour average demand
+#[derive(Debug, PartialOrd, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DemandPeak {
+	pub date: Tst,
+	pub demand: UnitValue<f64>,
+}
+
+/// Monthly maximum demand from the e-MUCS history buffer
+#[derive(Debug, PartialOrd, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MaximumDemandEntry {
+	/// Start of the month the entry belongs to
+	pub month: Tst,
+	pub date: Tst,
+	pub demand: UnitValue<f64>,
+}
+
+fn parse_buffer<'v>(mut parts: impl Iterator<Item = &'v str>) -> Vec<(Tst, UnitValue<u64>)> {
 	let mut out = vec![];
-	let mut parts = value.split(")(");
 	'extract: {
 		let Some(count) = parts.next() else {
 			break 'extract;
@@ -202,10 +375,10 @@ fn parse_buffer(value: &str) -> Vec<(Tst, UnitValue<u64>)> {
 		let Some(obis) = parts.next() else {
 			break 'extract;
 		};
-		let Some((obis, _)) = ParsedObis::parse(obis.as_bytes()) else {
+		let Ok(obis) = ObisCode::from_str(obis) else {
 			break 'extract;
 		};
-		if obis.obis != b"96.7.19" {
+		if (obis.c, obis.d, obis.e) != (96, 7, 19) {
 			break 'extract;
 		}
 		while let Some((date, value)) = parts.next().zip(parts.next()) {
@@ -221,7 +394,51 @@ fn parse_buffer(value: &str) -> Vec<(Tst, UnitValue<u64>)> {
 	out
 }
 
-fn parse_octet_string(value: &[u8]) -> Option<String> {
+fn parse_demand_history<'v>(mut parts: impl Iterator<Item = &'v str>) -> Vec<MaximumDemandEntry> {
+	let mut out = vec![];
+	'extract: {
+		let Some(count) = parts.next() else {
+			break 'extract;
+		};
+		let Ok(count) = u8::from_str(count) else {
+			break 'extract;
+		};
+		out.reserve(usize::from(count));
+		// the OBIS references of the captured objects: timestamp and value of the maximum demand
+		for _ in 0..2 {
+			let Some(obis) = parts.next() else {
+				break 'extract;
+			};
+			let Ok(obis) = ObisCode::from_str(obis) else {
+				break 'extract;
+			};
+			if (obis.c, obis.d, obis.e) != (1, 6, 0) {
+				break 'extract;
+			}
+		}
+		while let (Some(month), Some(date), Some(demand)) = (parts.next(), parts.next(), parts.next()) {
+			let (Some(month), Some(date)) = (Tst::try_from_bytes(month.as_bytes()), Tst::try_from_bytes(date.as_bytes())) else {
+				continue;
+			};
+			let Ok(demand) = UnitValue::from_str(demand) else {
+				continue;
+			};
+			out.push(MaximumDemandEntry { month, date, demand });
+		}
+	}
+	out
+}
+
+/// Parse the tariff from the raw value, which is an octet string by the specification, but most meters send the plain
+/// digits like `(0002)` instead of the hex-encoded `(30303032)`
+#[cfg(any(feature = "json", feature = "influx", feature = "mqtt", feature = "openmetrics"))]
+fn parse_tariff(value: &str) -> Option<u16> {
+	let digits =
+		parse_octet_string(value.as_bytes()).filter(|digits| value.len() == 8 && digits.bytes().all(|b| b.is_ascii_digit()));
+	digits.as_deref().unwrap_or(value).parse().ok()
+}
+
+pub(crate) fn parse_octet_string(value: &[u8]) -> Option<String> {
 	let mut out = String::with_capacity(value.len() / 2);
 	for bytes in value.chunks(2) {
 		out.push(char::from(u8::from_str_radix(str::from_utf8(bytes).ok()?, 16).ok()?));
@@ -230,149 +447,91 @@ fn parse_octet_string(value: &[u8]) -> Option<String> {
 }
 
 struct ParsedLine<'l> {
-	pub obis: ParsedObis<'l>,
-	pub value: &'l [u8],
+	pub obis: ObisCode,
+	/// Raw values of the parenthesized groups in the order of appearance
+	pub values: Vec<&'l [u8]>,
 }
 
 impl ParsedLine<'_> {
-	fn parse(line: &[u8]) -> Option<ParsedLine> {
-		#[expect(clippy::enum_variant_names)]
-		enum State {
-			WaitingForValue,
-			ReadingValue,
-			WaitingForAnotherValue,
-		}
+	fn parse(line: &[u8]) -> Result<ParsedLine<'_>, Error> {
+		let (obis, line) = ObisCode::parse_prefix(line).ok_or(Error::InvalidObis)?;
+		Ok(ParsedLine {
+			obis,
+			values: ValueGroups::new(line).collect::<Result<_, _>>()?,
+		})
+	}
 
-		let (obis, line) = ParsedObis::parse(line)?;
-		let mut out = ParsedLine { obis, value: &[] };
-		let mut state = State::WaitingForValue;
-		let mut start_offset = 0;
-		for (offset, byte) in line.iter().enumerate() {
-			let c = char::from(*byte);
-			state = match state {
-				State::WaitingForValue => match c {
-					'(' => {
-						start_offset = offset + 1;
-						State::ReadingValue
-					}
-					_ => return None,
-				},
-				State::ReadingValue => match c {
-					')' => {
-						out.value = line.get(start_offset..offset)?;
-						State::WaitingForAnotherValue
-					}
-					_ => State::ReadingValue,
-				},
-				State::WaitingForAnotherValue => match c {
-					'(' => State::ReadingValue,
-					_ => return None,
-				},
-			};
-		}
-		if matches!(state, State::WaitingForAnotherValue) {
-			Some(out)
-		} else {
-			None
-		}
+	/// First value of the line, the only one for the most objects
+	pub fn value(&self) -> &[u8] {
+		self.values.first().copied().unwrap_or_default()
 	}
 
 	pub fn value_str(&self) -> &str {
-		str::from_utf8(self.value).unwrap_or("")
+		str::from_utf8(self.value()).unwrap_or("")
+	}
+
+	pub fn value_strs(&self) -> impl DoubleEndedIterator<Item = &str> {
+		self.values.iter().map(|value| str::from_utf8(value).unwrap_or(""))
+	}
+
+	pub fn log_unknown(&self) {
+		debug!(
+			"Unknown OBIS: {} with values: {:?}",
+			self.obis,
+			self.value_strs().collect::<Vec<_>>()
+		);
 	}
 }
 
-#[derive(Debug)]
-struct ParsedObis<'l> {
-	/// Group A: Specifies the medium of the object (0= Abstract Objects, 1=Electricity, 7=gas, Etc.)
-	pub medium: u8,
-	/// Group B: Specifies the channel (useful, for example, when a data concentrator is connected to several meters).
-	pub channel: u8,
-	/// Group C: Specifies the Physical Value (Current, Voltage, Energy, etc.).
-	/// Group D: Identifies types, or the result of the processing of physical quantities identified by values in value groups A and C, according to various specific algorithms.
-	/// Group E: Identifies further processing or classification of quantities identified by values in value groups A to D.
-	pub obis: &'l [u8],
+/// Iterator over the parenthesized value groups of an object line following the OBIS code
+///
+/// Yields the values without the parentheses, [Error::MalformedLine] is returned once if the groups are not properly
+/// enclosed or there are none.
+#[derive(Clone)]
+struct ValueGroups<'l> {
+	rest: &'l [u8],
+	first: bool,
 }
 
-impl ParsedObis<'_> {
-	fn parse(line: &[u8]) -> Option<(ParsedObis, &[u8])> {
-		enum State {
-			WaitingForObisMedium,
-			ReadingObisMedium,
-			WaitingForObisChannel,
-			ReadingObisChannel,
-			WaitingForObis,
-			ReadingObis,
-			Done,
+impl<'l> ValueGroups<'l> {
+	fn new(values: &'l [u8]) -> Self {
+		Self {
+			rest: values,
+			first: true,
 		}
+	}
+}
 
-		let mut out = ParsedObis {
-			medium: 0,
-			channel: 0,
-			obis: &[],
-		};
-		let mut state = State::WaitingForObisMedium;
-		let mut start_offset = 0;
-		// todo: more careful parsing of obis sub-values
-		for (offset, byte) in line.iter().enumerate() {
-			let c = char::from(*byte);
-			state = match state {
-				State::WaitingForObisMedium => match c {
-					c if c.is_ascii_digit() => {
-						start_offset = offset;
-						State::ReadingObisMedium
-					}
-					_ => return None,
-				},
-				State::ReadingObisMedium => match c {
-					c if c.is_ascii_digit() => State::ReadingObisMedium,
-					'-' => {
-						out.medium = str::from_utf8(line.get(start_offset..offset)?).ok()?.parse().ok()?;
-						State::WaitingForObisChannel
-					}
-					_ => return None,
-				},
-				State::WaitingForObisChannel => match c {
-					c if c.is_ascii_digit() => {
-						start_offset = offset;
-						State::ReadingObisChannel
-					}
-					_ => return None,
-				},
-				State::ReadingObisChannel => match c {
-					c if c.is_ascii_digit() => State::ReadingObisChannel,
-					':' => {
-						out.channel = str::from_utf8(line.get(start_offset..offset)?).ok()?.parse().ok()?;
-						State::WaitingForObis
-					}
-					_ => return None,
-				},
-				State::WaitingForObis => match c {
-					c if c.is_ascii_digit() => {
-						start_offset = offset;
-						State::ReadingObis
-					}
-					_ => return None,
-				},
-				State::ReadingObis => match c {
-					c if c.is_ascii_digit() || c == '.' => State::ReadingObis,
-					'(' => {
-						out.obis = line.get(start_offset..offset)?;
-						start_offset = offset;
-						State::Done
-					}
-					_ => return None,
-				},
-				State::Done => break,
-			};
+impl<'l> Iterator for ValueGroups<'l> {
+	type Item = Result<&'l [u8], Error>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if !self.first {
+			// values of the legacy objects can continue on the next line
+			let start = self
+				.rest
+				.iter()
+				.position(|&b| b != b'\r' && b != b'\n')
+				.unwrap_or(self.rest.len());
+			self.rest = &self.rest[start..];
+			if self.rest.is_empty() {
+				return None;
+			}
 		}
-		match state {
-			State::ReadingObis => {
-				out.obis = line.get(start_offset..)?;
-				Some((out, &[]))
+		self.first = false;
+		let value = self
+			.rest
+			.strip_prefix(b"(")
+			.and_then(|rest| Some(rest.split_at(rest.iter().position(|&b| b == b')')?)));
+		match value {
+			Some((value, rest)) => {
+				self.rest = &rest[1..];
+				Some(Ok(value))
+			}
+			None => {
+				self.rest = &[];
+				Some(Err(Error::MalformedLine))
 			}
-			State::Done => Some((out, line.get(start_offset..)?)),
-			_ => None,
 		}
 	}
 }
@@ -0,0 +1,289 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::str::FromStr;
+use core::{iter, str};
+
+use log::warn;
+
+use super::parser::{FramedLine, LineFramer};
+use super::{Error, ParsedLine, Telegram, ValueGroups, parse_octet_string};
+use crate::{CrcMode, ObisCode, Tst, UnitValue};
+
+/// Zero-copy view of a valid telegram borrowing from the input buffer
+///
+/// Parsing only splits the telegram into the COSEM objects and checks the CRC, the values are decoded on demand. The
+/// common electricity fields have the accessors borrowing the unit from the input, the rest of the objects are available
+/// through [TelegramRef::get]. Use [TelegramRef::to_telegram] to get the fully decoded owned [Telegram].
+///
+/// ```
+/// use dsmr_parse::{ObisCode, TelegramRef};
+///
+/// let telegram_data = b"/XMX5LGBBFG1009394887\r\n\r\n1-3:0.2.8(42)\r\n1-0:1.8.1(004169.415*kWh)\r\n!9FC1\r\n";
+///
+/// let telegram = TelegramRef::parse(telegram_data).unwrap().unwrap();
+/// assert_eq!("XMX5LGBBFG1009394887", telegram.ident);
+/// let consumed = telegram.electricity_consumed_tariff_1().unwrap();
+/// assert_eq!(4169.415, consumed.value);
+/// assert_eq!(Some("kWh"), consumed.unit);
+/// assert_eq!(Some("42"), telegram.get(ObisCode::VERSION).map(|version| version.value()));
+/// ```
+#[derive(Debug, PartialOrd, PartialEq, Clone, Default)]
+pub struct TelegramRef<'a> {
+	pub ident: &'a str,
+	/// All COSEM objects of the telegram in the order of appearance
+	pub objects: Vec<CosemObjectRef<'a>>,
+	/// See [Telegram::crc_valid]
+	pub crc_valid: bool,
+	pub crc_actual: u16,
+	pub crc_expected: Option<u16>,
+}
+
+impl<'a> TelegramRef<'a> {
+	/// Try to parse a single telegram from a buffer
+	///
+	/// The data before the telegram header and after the CRC line is ignored. Returns `Ok(None)` if the buffer doesn't
+	/// contain a complete telegram.
+	pub fn parse(buf: &'a [u8]) -> Result<Option<Self>, Error> {
+		Self::parse_with_crc_mode(buf, CrcMode::default())
+	}
+
+	/// Try to parse a single telegram from a buffer with the specified CRC handling
+	///
+	/// The values are decoded on demand, so there is no lenient mode, see [TelegramRef::to_telegram].
+	pub fn parse_with_crc_mode(buf: &'a [u8], crc_mode: CrcMode) -> Result<Option<Self>, Error> {
+		let mut lines = buf
+			.split(|&b| b == b'\n')
+			.map(|line| line.strip_suffix(b"\r").unwrap_or(line));
+		let Some(header) = lines.by_ref().find(|line| line.starts_with(b"/")) else {
+			return Ok(None);
+		};
+		let mut framer = LineFramer::new(crc_mode);
+		let mut out = Self::default();
+		// the object line is held back because the values of legacy objects can continue on the following lines
+		let mut pending_line: Option<&'a [u8]> = None;
+		for line in iter::once(header).chain(lines) {
+			match framer.push_line(line)? {
+				FramedLine::Ignored => {}
+				FramedLine::Header { ident, truncated } => {
+					if let Some(lines_read) = truncated {
+						return Err(Error::TruncatedTelegram { lines_read });
+					}
+					out = Self {
+						ident: str::from_utf8(ident)?,
+						objects: Vec::with_capacity(40),
+						..Self::default()
+					};
+				}
+				FramedLine::Object => {
+					out.push_object(pending_line.take());
+					pending_line = (!line.is_empty()).then_some(line);
+				}
+				FramedLine::Continuation => {
+					if let Some(pending) = pending_line {
+						let start = pending.as_ptr() as usize - buf.as_ptr() as usize;
+						let end = line.as_ptr() as usize - buf.as_ptr() as usize + line.len();
+						pending_line = buf.get(start..end);
+					}
+				}
+				FramedLine::End(crc_str) => {
+					out.push_object(pending_line.take());
+					let crc = framer.check_crc(crc_str)?;
+					out.crc_valid = crc.valid;
+					out.crc_actual = crc.actual;
+					out.crc_expected = crc.expected;
+					return Ok(Some(out));
+				}
+			}
+		}
+		Ok(None)
+	}
+
+	fn push_object(&mut self, line: Option<&'a [u8]>) {
+		let Some(line) = line else {
+			return;
+		};
+		let parsed = ObisCode::parse_prefix(line)
+			.ok_or(Error::InvalidObis)
+			.and_then(|(obis, values)| {
+				// validate the values upfront, so that they can be iterated infallibly later
+				ValueGroups::new(values).try_for_each(|value| value.map(|_| ()))?;
+				Ok(CosemObjectRef {
+					obis,
+					raw_values: str::from_utf8(values)?,
+				})
+			});
+		match parsed {
+			Ok(object) => self.objects.push(object),
+			Err(e) => warn!("Skipping the malformed line ({e}): {}", String::from_utf8_lossy(line)),
+		}
+	}
+
+	/// Find the first object with the matching OBIS code
+	pub fn get(&self, obis: ObisCode) -> Option<&CosemObjectRef<'a>> {
+		self.objects.iter().find(|object| object.obis == obis)
+	}
+
+	/// See [Telegram::version]
+	pub fn version(&self) -> Option<&'a str> {
+		self.get(ObisCode::VERSION).map(CosemObjectRef::value)
+	}
+
+	/// See [Telegram::electricity_date]
+	pub fn electricity_date(&self) -> Option<Tst> {
+		self.get(ObisCode::ELECTRICITY_DATE)?.tst()
+	}
+
+	/// See [Telegram::electricity_consumed_tariff_1]
+	pub fn electricity_consumed_tariff_1(&self) -> Option<UnitValue<f64, &'a str>> {
+		self.unit_value(ObisCode::ELECTRICITY_CONSUMED_TARIFF_1)
+	}
+
+	/// See [Telegram::electricity_consumed_tariff_2]
+	pub fn electricity_consumed_tariff_2(&self) -> Option<UnitValue<f64, &'a str>> {
+		self.unit_value(ObisCode::ELECTRICITY_CONSUMED_TARIFF_2)
+	}
+
+	/// See [Telegram::electricity_generated_tariff_1]
+	pub fn electricity_generated_tariff_1(&self) -> Option<UnitValue<f64, &'a str>> {
+		self.unit_value(ObisCode::ELECTRICITY_GENERATED_TARIFF_1)
+	}
+
+	/// See [Telegram::electricity_generated_tariff_2]
+	pub fn electricity_generated_tariff_2(&self) -> Option<UnitValue<f64, &'a str>> {
+		self.unit_value(ObisCode::ELECTRICITY_GENERATED_TARIFF_2)
+	}
+
+	/// See [Telegram::power]
+	pub fn power(&self) -> Option<UnitValue<f64, &'a str>> {
+		self.unit_value(ObisCode::POWER)
+	}
+
+	/// See [Telegram::return_power]
+	pub fn return_power(&self) -> Option<UnitValue<f64, &'a str>> {
+		self.unit_value(ObisCode::RETURN_POWER)
+	}
+
+	/// See [Telegram::voltage_l1]
+	pub fn voltage_l1(&self) -> Option<UnitValue<f64, &'a str>> {
+		self.unit_value(ObisCode::VOLTAGE_L1)
+	}
+
+	/// See [Telegram::voltage_l2]
+	pub fn voltage_l2(&self) -> Option<UnitValue<f64, &'a str>> {
+		self.unit_value(ObisCode::VOLTAGE_L2)
+	}
+
+	/// See [Telegram::voltage_l3]
+	pub fn voltage_l3(&self) -> Option<UnitValue<f64, &'a str>> {
+		self.unit_value(ObisCode::VOLTAGE_L3)
+	}
+
+	/// See [Telegram::current_l1]
+	pub fn current_l1(&self) -> Option<UnitValue<u16, &'a str>> {
+		self.unit_value(ObisCode::CURRENT_L1)
+	}
+
+	/// See [Telegram::current_l2]
+	pub fn current_l2(&self) -> Option<UnitValue<u16, &'a str>> {
+		self.unit_value(ObisCode::CURRENT_L2)
+	}
+
+	/// See [Telegram::current_l3]
+	pub fn current_l3(&self) -> Option<UnitValue<u16, &'a str>> {
+		self.unit_value(ObisCode::CURRENT_L3)
+	}
+
+	/// See [Telegram::power_l1]
+	pub fn power_l1(&self) -> Option<UnitValue<f64, &'a str>> {
+		self.unit_value(ObisCode::POWER_L1)
+	}
+
+	/// See [Telegram::power_l2]
+	pub fn power_l2(&self) -> Option<UnitValue<f64, &'a str>> {
+		self.unit_value(ObisCode::POWER_L2)
+	}
+
+	/// See [Telegram::power_l3]
+	pub fn power_l3(&self) -> Option<UnitValue<f64, &'a str>> {
+		self.unit_value(ObisCode::POWER_L3)
+	}
+
+	/// See [Telegram::return_power_l1]
+	pub fn return_power_l1(&self) -> Option<UnitValue<f64, &'a str>> {
+		self.unit_value(ObisCode::RETURN_POWER_L1)
+	}
+
+	/// See [Telegram::return_power_l2]
+	pub fn return_power_l2(&self) -> Option<UnitValue<f64, &'a str>> {
+		self.unit_value(ObisCode::RETURN_POWER_L2)
+	}
+
+	/// See [Telegram::return_power_l3]
+	pub fn return_power_l3(&self) -> Option<UnitValue<f64, &'a str>> {
+		self.unit_value(ObisCode::RETURN_POWER_L3)
+	}
+
+	/// Value of the object with the unit, `None` if it's missing or can't be decoded
+	fn unit_value<T: FromStr>(&self, obis: ObisCode) -> Option<UnitValue<T, &'a str>> {
+		self.get(obis)?.unit_value().ok()
+	}
+
+	/// Decode all objects into an owned [Telegram]
+	///
+	/// Fails on the first value that can't be decoded, the objects that can't be parsed at all are skipped by
+	/// [TelegramRef::parse].
+	pub fn to_telegram(&self) -> Result<Telegram, Error> {
+		let mut out = Telegram {
+			ident: self.ident.into(),
+			crc_valid: self.crc_valid,
+			crc_actual: self.crc_actual,
+			crc_expected: self.crc_expected,
+			..Telegram::default()
+		};
+		for object in &self.objects {
+			out.apply_line(&ParsedLine {
+				obis: object.obis,
+				values: object.values().map(str::as_bytes).collect(),
+			})?;
+		}
+		Ok(out)
+	}
+}
+
+/// Single COSEM object of the [TelegramRef]
+#[derive(Debug, PartialOrd, PartialEq, Clone)]
+pub struct CosemObjectRef<'a> {
+	pub obis: ObisCode,
+	/// Raw parenthesized value groups of the object as they appear in the telegram, e.g. `(230.1*V)`
+	pub raw_values: &'a str,
+}
+
+impl<'a> CosemObjectRef<'a> {
+	/// Values of the object without the surrounding parentheses, one per parenthesized group
+	pub fn values(&self) -> impl Iterator<Item = &'a str> + use<'a> {
+		// the groups are validated on parse, and they are split at ASCII parentheses, so the slices are valid UTF-8
+		ValueGroups::new(self.raw_values.as_bytes())
+			.filter_map(Result::ok)
+			.map(|value| str::from_utf8(value).unwrap_or_default())
+	}
+
+	/// First value of the object, the only one for the most objects
+	pub fn value(&self) -> &'a str {
+		self.values().next().unwrap_or_default()
+	}
+
+	/// Parse the value as a number with an optional unit borrowed from the input
+	pub fn unit_value<T: FromStr>(&self) -> Result<UnitValue<T, &'a str>, T::Err> {
+		UnitValue::parse_borrowed(self.value())
+	}
+
+	/// Parse the value as a timestamp
+	pub fn tst(&self) -> Option<Tst> {
+		Tst::try_from_bytes(self.value().as_bytes())
+	}
+
+	/// Decode the value as a hex-encoded octet string
+	pub fn octet_string(&self) -> Option<String> {
+		parse_octet_string(self.value().as_bytes())
+	}
+}
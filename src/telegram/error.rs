@@ -1,23 +1,90 @@
+use alloc::boxed::Box;
+use alloc::string::{FromUtf8Error, String};
 use core::fmt;
 use core::num::{ParseFloatError, ParseIntError};
 use core::str::Utf8Error;
+#[cfg(feature = "std")]
 use std::io;
-use std::string::FromUtf8Error;
 
+use super::MAX_LINE_LEN;
+use crate::ObisCode;
+
+/// Error of reading or parsing the telegram
+///
+/// New variants can be added in the minor releases, and some of them are only present with the corresponding features.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum Error {
+	#[cfg(feature = "std")]
 	Io(io::Error),
+	#[cfg(feature = "embedded-io")]
+	EmbeddedIo(embedded_io::ErrorKind),
 	NonUtf8Parse(Utf8Error),
 	NonUtf8Val(FromUtf8Error),
 	InvalidInt(ParseIntError),
 	InvalidFloat(ParseFloatError),
 	CrcMismatch(u16, u16),
+	/// Error in the specific line of the telegram
+	Line(Box<LineError>),
+	/// End of the telegram (`!` line) without the preceding header
+	MissingHeader,
+	/// Telegram that didn't reach its end, either interrupted by the header of the next one or by the end of the stream
+	TruncatedTelegram {
+		/// Number of the lines of the telegram read before the interruption, including the header
+		lines_read: usize,
+	},
+	/// Line is longer than [MAX_LINE_LEN]
+	LineTooLong,
+	/// Line doesn't start with a valid OBIS code, such lines are skipped by the parser
+	InvalidObis,
+	/// Values of the object are not enclosed in parentheses, such lines are skipped by the parser and only reported in
+	/// [Telegram::field_errors](crate::Telegram::field_errors) in the lenient mode
+	MalformedLine,
+	/// Reading or writing the CSV failed
+	#[cfg(feature = "csv")]
+	Csv(csv::Error),
+	/// Value in the CSV column can't be decoded into the corresponding field
+	#[cfg(feature = "csv")]
+	InvalidCsvValue {
+		column: String,
+		value: String,
+	},
+}
+
+impl Error {
+	/// Wrap the error with the context of the telegram line it occurred in
+	pub(crate) fn in_line(self, line: &[u8], line_number: usize, offset: usize) -> Self {
+		Error::Line(Box::new(LineError {
+			obis: ObisCode::parse_prefix(line).map(|(obis, _)| obis),
+			line: String::from_utf8_lossy(line).into_owned(),
+			line_number,
+			offset,
+			error: self,
+		}))
+	}
+}
+
+/// Context of the error that occurred in the specific line of the telegram
+#[derive(Debug)]
+pub struct LineError {
+	/// OBIS code of the object, `None` if the line doesn't start with a valid one
+	pub obis: Option<ObisCode>,
+	/// Raw text of the line, including the continuation lines of the legacy objects
+	pub line: String,
+	/// Number of the line within the telegram, the header is line 1
+	pub line_number: usize,
+	/// Byte offset of the line from the start of the header, assuming CRLF line terminators
+	pub offset: usize,
+	pub error: Error,
 }
 
 impl fmt::Display for Error {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		match self {
+			#[cfg(feature = "std")]
 			Error::Io(e) => write!(f, "IO error: {e}, details: {e:?}"),
+			#[cfg(feature = "embedded-io")]
+			Error::EmbeddedIo(e) => write!(f, "Embedded IO error: {e:?}"),
 			Error::NonUtf8Parse(e) => write!(f, "Non UTF-8 string: {e}, details: {e:?}"),
 			Error::NonUtf8Val(e) => write!(f, "Non UTF-8 string: {e}, details: {e:?}"),
 			Error::InvalidInt(e) => write!(f, "Invalid integer: {e}, details: {e:?}"),
@@ -26,16 +93,34 @@ impl fmt::Display for Error {
 				f,
 				"Telegram CRC mismatch, computed: {actual:X}, doesn't match expected: {expected:X}"
 			),
+			Error::Line(e) => write!(f, "{} in line {} at offset {}: {}", e.error, e.line_number, e.offset, e.line),
+			Error::MissingHeader => write!(f, "Telegram end without the header"),
+			Error::TruncatedTelegram { lines_read } => write!(f, "Telegram truncated after {lines_read} lines"),
+			Error::LineTooLong => write!(f, "Line is longer than {MAX_LINE_LEN} bytes"),
+			Error::InvalidObis => write!(f, "Invalid OBIS code"),
+			Error::MalformedLine => write!(f, "Object values are not enclosed in parentheses"),
+			#[cfg(feature = "csv")]
+			Error::Csv(e) => write!(f, "CSV error: {e}"),
+			#[cfg(feature = "csv")]
+			Error::InvalidCsvValue { column, value } => write!(f, "Invalid value in CSV column {column}: {value}"),
 		}
 	}
 }
 
+#[cfg(feature = "std")]
 impl From<io::Error> for Error {
 	fn from(err: io::Error) -> Self {
 		Error::Io(err)
 	}
 }
 
+#[cfg(feature = "csv")]
+impl From<csv::Error> for Error {
+	fn from(err: csv::Error) -> Self {
+		Error::Csv(err)
+	}
+}
+
 impl From<Utf8Error> for Error {
 	fn from(err: Utf8Error) -> Self {
 		Error::NonUtf8Parse(err)
@@ -60,4 +145,13 @@ impl From<ParseFloatError> for Error {
 	}
 }
 
-impl std::error::Error for Error {}
+impl core::error::Error for Error {
+	fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+		match self {
+			Error::Line(e) => Some(&e.error),
+			#[cfg(feature = "csv")]
+			Error::Csv(e) => Some(e),
+			_ => None,
+		}
+	}
+}
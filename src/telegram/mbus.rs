@@ -0,0 +1,150 @@
+use alloc::string::String;
+
+use super::{Error, ParsedLine, parse_octet_string};
+use crate::{Tst, UnitValue};
+
+/// Device connected to the meter over M-Bus (gas, water, heat meter, slave electricity meter, etc.)
+#[derive(Debug, PartialOrd, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MbusDevice {
+	/// M-Bus channel the device is connected to (OBIS value group B), 1..=4
+	pub channel: u8,
+	pub device_type: Option<MbusDeviceType>,
+	pub equipment_id: Option<String>,
+	/// Valve position: 0 - closed, 1 - open, 2 - released
+	pub valve_position: Option<u8>,
+	/// Timestamp of the last value captured from the device
+	pub capture_date: Option<Tst>,
+	/// Last value captured from the device (e.g. gas or water volume)
+	pub capture_value: Option<UnitValue<f64>>,
+}
+
+impl MbusDevice {
+	pub(super) const CHANNELS: [u8; 4] = [1, 2, 3, 4];
+
+	pub fn new(channel: u8) -> Self {
+		Self {
+			channel,
+			..Self::default()
+		}
+	}
+
+	/// Update the corresponding field from the parsed line, returns `false` if the object isn't decoded into any field
+	pub(super) fn apply_line(&mut self, line: &ParsedLine) -> Result<bool, Error> {
+		match (line.obis.c, line.obis.d, line.obis.e) {
+			(24, 1, 0) => self.device_type = Some(MbusDeviceType::from(line.value_str().parse::<u8>()?)),
+			(96, 1, 0) | (96, 1, 1) => self.equipment_id = parse_octet_string(line.value()),
+			(24, 4, 0) => self.valve_position = Some(line.value_str().parse()?),
+			// 24.2.1 - temperature corrected value, 24.2.3 - uncorrected value (e-MUCS)
+			(24, 2, 1) | (24, 2, 3) => {
+				self.capture_date = Tst::try_from_bytes(line.value());
+				if let Some(value) = line.value_strs().nth(1) {
+					self.capture_value = Some(value.parse()?);
+				}
+			}
+			(24, 3, 0) => {
+				// legacy (DSMR 2.2/3.0) reading: (date)(status)(period)(count)(obis)(unit), followed by (value)
+				self.capture_date = Tst::try_from_bytes(line.value());
+				let mut values = line.value_strs();
+				let value = values.next_back();
+				let unit = values.next_back();
+				if let Some((value, unit)) = value.zip(unit) {
+					self.capture_value = Some(UnitValue::with_unit(value.parse()?, unit));
+				}
+			}
+			_ => {
+				line.log_unknown();
+				return Ok(false);
+			}
+		}
+		Ok(true)
+	}
+}
+
+/// Type of the M-Bus device according to EN 13757-3
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MbusDeviceType {
+	/// 0x01
+	Oil,
+	/// 0x02, slave electricity meter
+	Electricity,
+	/// 0x03
+	Gas,
+	/// 0x04, heat meter (outlet)
+	Heat,
+	/// 0x05
+	Steam,
+	/// 0x06, water 30..90 °C
+	WarmWater,
+	/// 0x07
+	Water,
+	/// 0x08
+	HeatCostAllocator,
+	/// 0x09
+	CompressedAir,
+	/// 0x0A, cooling load meter (outlet)
+	CoolingLoadOutlet,
+	/// 0x0B, cooling load meter (inlet)
+	CoolingLoadInlet,
+	/// 0x0C, heat meter (inlet)
+	HeatInlet,
+	/// 0x0D, combined heat / cooling load meter
+	HeatCoolingLoad,
+	/// 0x15, water >= 90 °C
+	HotWater,
+	/// 0x16
+	ColdWater,
+	/// 0x17, dual register (hot/cold) water meter
+	DualRegisterWater,
+	/// Any other device type code
+	Other(u8),
+}
+
+impl From<u8> for MbusDeviceType {
+	fn from(code: u8) -> Self {
+		match code {
+			0x01 => Self::Oil,
+			0x02 => Self::Electricity,
+			0x03 => Self::Gas,
+			0x04 => Self::Heat,
+			0x05 => Self::Steam,
+			0x06 => Self::WarmWater,
+			0x07 => Self::Water,
+			0x08 => Self::HeatCostAllocator,
+			0x09 => Self::CompressedAir,
+			0x0A => Self::CoolingLoadOutlet,
+			0x0B => Self::CoolingLoadInlet,
+			0x0C => Self::HeatInlet,
+			0x0D => Self::HeatCoolingLoad,
+			0x15 => Self::HotWater,
+			0x16 => Self::ColdWater,
+			0x17 => Self::DualRegisterWater,
+			code => Self::Other(code),
+		}
+	}
+}
+
+impl From<MbusDeviceType> for u8 {
+	fn from(device_type: MbusDeviceType) -> Self {
+		match device_type {
+			MbusDeviceType::Oil => 0x01,
+			MbusDeviceType::Electricity => 0x02,
+			MbusDeviceType::Gas => 0x03,
+			MbusDeviceType::Heat => 0x04,
+			MbusDeviceType::Steam => 0x05,
+			MbusDeviceType::WarmWater => 0x06,
+			MbusDeviceType::Water => 0x07,
+			MbusDeviceType::HeatCostAllocator => 0x08,
+			MbusDeviceType::CompressedAir => 0x09,
+			MbusDeviceType::CoolingLoadOutlet => 0x0A,
+			MbusDeviceType::CoolingLoadInlet => 0x0B,
+			MbusDeviceType::HeatInlet => 0x0C,
+			MbusDeviceType::HeatCoolingLoad => 0x0D,
+			MbusDeviceType::HotWater => 0x15,
+			MbusDeviceType::ColdWater => 0x16,
+			MbusDeviceType::DualRegisterWater => 0x17,
+			MbusDeviceType::Other(code) => code,
+		}
+	}
+}
@@ -0,0 +1,333 @@
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::mem;
+
+use log::{trace, warn};
+
+use super::{CRC_PREFIX, CRLF, Error, FieldError, MAX_LINE_LEN, ParsedLine, Telegram, ValueGroups, check_crc};
+use crate::{CrcMode, ObisCode, ParseOptions};
+
+enum FramerState {
+	/// Skipping the rest of a telegram that started before the parser or failed to parse
+	Skipping,
+	WaitingForHeader,
+	ReadingHeader,
+	ReadingMessage,
+}
+
+/// Line of the telegram as classified by [LineFramer]
+pub(crate) enum FramedLine<'l> {
+	/// Line outside of a telegram or the empty line after the header
+	Ignored,
+	/// Header of a new telegram with the meter ident, `truncated` is the number of lines read of the unfinished previous one
+	Header { ident: &'l [u8], truncated: Option<usize> },
+	/// First line of a new object (or an empty line), the previous object is complete
+	Object,
+	/// Continuation of the values of the previous object on the next line, only used by the legacy objects
+	Continuation,
+	/// CRC line finishing the telegram with the CRC string after `!`, the last object is complete
+	End(&'l [u8]),
+}
+
+/// Result of the CRC check of a finished telegram
+pub(crate) struct CrcCheck {
+	pub valid: bool,
+	pub actual: u16,
+	pub expected: Option<u16>,
+}
+
+/// Line-level telegram state machine shared by [TelegramParser] and [TelegramRef](super::TelegramRef)
+///
+/// It recognizes the header, object and CRC lines, keeps track of the CRC and the position of the line within the
+/// telegram, while the objects themselves are collected by the caller. After a finished telegram or an error it goes back
+/// to waiting for the next `/` header.
+pub(crate) struct LineFramer {
+	state: FramerState,
+	crc: crc16::State<crc16::ARC>,
+	crc_mode: CrcMode,
+	/// Whether the last line was an object line that can be continued
+	continuable: bool,
+	/// Whether the telegram has any of the DSMR 4.0+ markers, only the telegrams without them may end with a bare `!`
+	modern: bool,
+	/// Line number and offset of the last line within the telegram
+	line_pos: (usize, usize),
+	/// Number of the telegram lines read so far and their length including CRLF
+	lines_read: usize,
+	bytes_read: usize,
+	/// Number of bytes before the header of the current telegram that didn't belong to any telegram
+	skipped_bytes: usize,
+}
+
+impl LineFramer {
+	pub fn new(crc_mode: CrcMode) -> Self {
+		Self {
+			state: FramerState::Skipping,
+			crc: crc16::State::new(),
+			crc_mode,
+			continuable: false,
+			modern: false,
+			line_pos: (0, 0),
+			lines_read: 0,
+			bytes_read: 0,
+			skipped_bytes: 0,
+		}
+	}
+
+	/// Classify the next line (without the trailing line terminator)
+	pub fn push_line<'l>(&mut self, line: &'l [u8]) -> Result<FramedLine<'l>, Error> {
+		let line_number = self.lines_read + 1;
+		let offset = self.bytes_read;
+		self.line_pos = (line_number, offset);
+		self.lines_read += 1;
+		self.bytes_read += line.len() + CRLF.len();
+		if line.len() > MAX_LINE_LEN {
+			return Err(match self.state {
+				FramerState::ReadingHeader | FramerState::ReadingMessage => {
+					self.skip();
+					Error::LineTooLong.in_line(line, line_number, offset)
+				}
+				_ => Error::LineTooLong,
+			});
+		}
+		if let Some(ident) = line.strip_prefix(b"/") {
+			let truncated = matches!(self.state, FramerState::ReadingHeader | FramerState::ReadingMessage);
+			self.skipped_bytes = if truncated {
+				0
+			} else {
+				offset
+			};
+			// a header always starts a new telegram, even if the previous one wasn't finished
+			self.crc = crc16::State::new();
+			self.crc.update(line);
+			self.crc.update(CRLF);
+			self.continuable = false;
+			self.modern = false;
+			self.line_pos = (1, 0);
+			self.lines_read = 1;
+			self.bytes_read = line.len() + CRLF.len();
+			self.state = FramerState::ReadingHeader;
+			return Ok(FramedLine::Header {
+				ident,
+				truncated: truncated.then_some(line_number - 1),
+			});
+		}
+		match self.state {
+			FramerState::Skipping => {
+				if line.starts_with(CRC_PREFIX) {
+					self.state = FramerState::WaitingForHeader;
+				}
+			}
+			FramerState::WaitingForHeader => {
+				if line.starts_with(CRC_PREFIX) {
+					return Err(Error::MissingHeader);
+				}
+			}
+			FramerState::ReadingHeader => {
+				if line.is_empty() {
+					self.crc.update(CRLF);
+					self.state = FramerState::ReadingMessage;
+				} else {
+					self.skip();
+				}
+			}
+			FramerState::ReadingMessage => {
+				if let Some(crc_str) = line.strip_prefix(CRC_PREFIX) {
+					self.crc.update(CRC_PREFIX);
+					self.state = FramerState::WaitingForHeader;
+					self.lines_read = 0;
+					self.bytes_read = 0;
+					return Ok(FramedLine::End(crc_str));
+				}
+				self.crc.update(line);
+				self.crc.update(CRLF);
+				if line.starts_with(b"(") && self.continuable {
+					self.modern |= has_dst_timestamp(line);
+					return Ok(FramedLine::Continuation);
+				}
+				self.continuable = !line.is_empty();
+				self.modern |= is_modern_object(line);
+				return Ok(FramedLine::Object);
+			}
+		}
+		Ok(FramedLine::Ignored)
+	}
+
+	/// Check the CRC string of the [FramedLine::End] line against the CRC computed over the telegram
+	pub fn check_crc(&self, crc_str: &[u8]) -> Result<CrcCheck, Error> {
+		let actual = self.crc.get();
+		let (valid, expected) = check_crc(actual, crc_str, !self.modern, self.crc_mode)?;
+		Ok(CrcCheck { valid, actual, expected })
+	}
+
+	/// Line number and offset of the last line within the telegram
+	pub fn line_pos(&self) -> (usize, usize) {
+		self.line_pos
+	}
+
+	/// Signal the end of the stream, returns the number of lines read if it ended in the middle of a telegram
+	pub fn finish(&mut self) -> Option<usize> {
+		let truncated = matches!(self.state, FramerState::ReadingHeader | FramerState::ReadingMessage);
+		let lines_read = self.lines_read;
+		// the next stream can start in the middle of a telegram
+		self.skip();
+		truncated.then_some(lines_read)
+	}
+
+	/// Skip the rest of the telegram if its objects are being read
+	pub fn skip_message(&mut self) {
+		if matches!(self.state, FramerState::ReadingMessage) {
+			self.skip();
+		}
+	}
+
+	/// Skip the rest of the current telegram, the skipped bytes are counted from here
+	pub fn skip(&mut self) {
+		self.state = FramerState::Skipping;
+		self.continuable = false;
+		self.lines_read = 0;
+		self.bytes_read = 0;
+	}
+}
+
+/// Whether the object line is one of the DSMR 4.0+ markers
+///
+/// These are the version (`0.2.8`) that's mandatory since DSMR 4.0, the Belgian e-MUCS version (`96.1.4`) that replaces it,
+/// and the timestamps with the DST flag that the legacy (DSMR 2.2/3.0) meters don't report.
+fn is_modern_object(line: &[u8]) -> bool {
+	ObisCode::parse_prefix(line)
+		.is_some_and(|(obis, values)| matches!((obis.c, obis.d, obis.e), (0, 2, 8) | (96, 1, 4)) || has_dst_timestamp(values))
+}
+
+/// Whether any of the value groups is a timestamp with the DST flag, e.g. `(190101125431W)`
+fn has_dst_timestamp(values: &[u8]) -> bool {
+	ValueGroups::new(values)
+		.filter_map(Result::ok)
+		.any(|value| value.len() == 13 && value[..12].iter().all(u8::is_ascii_digit) && matches!(value[12], b'S' | b'W'))
+}
+
+/// Line-based telegram parser producing owned [Telegram]s
+///
+/// Lines are fed one by one (without the trailing line terminator), the parser yields the telegram once its CRC line is
+/// seen, see [LineFramer] for the details. It can be reused for a continuous stream of telegrams.
+///
+/// Each object line is held back until the next line is seen, because the legacy (DSMR 2.2/3.0) objects can have their
+/// values continued on the following lines starting with `(`.
+pub(crate) struct TelegramParser {
+	framer: LineFramer,
+	telegram: Telegram,
+	pending_line: Vec<u8>,
+	/// Line number and offset of the pending line within the telegram
+	pending_pos: (usize, usize),
+	options: ParseOptions,
+}
+
+impl TelegramParser {
+	pub fn new(options: ParseOptions) -> Self {
+		Self {
+			framer: LineFramer::new(options.crc),
+			telegram: Telegram::default(),
+			pending_line: Vec::with_capacity(64),
+			pending_pos: (0, 0),
+			options,
+		}
+	}
+
+	/// Feed the next line to the parser, returns `Some` once a telegram is completed or failed to parse
+	pub fn push_line(&mut self, line: &[u8]) -> Option<Result<Telegram, Error>> {
+		trace!("Got line: {}", String::from_utf8_lossy(line));
+		let res = self.process_line(line);
+		if res.is_err() {
+			// skip the rest of the broken telegram until the next header
+			self.framer.skip_message();
+			self.pending_line.clear();
+		}
+		res.transpose()
+	}
+
+	/// Signal the end of the stream, returns [Error::TruncatedTelegram] if it ended in the middle of a telegram
+	pub fn finish(&mut self) -> Option<Result<Telegram, Error>> {
+		self.pending_line.clear();
+		self
+			.framer
+			.finish()
+			.map(|lines_read| Err(Error::TruncatedTelegram { lines_read }))
+	}
+
+	/// Number of bytes skipped before the header of the last telegram, assuming CRLF line terminators
+	pub fn skipped_bytes(&self) -> usize {
+		self.framer.skipped_bytes
+	}
+
+	fn process_line(&mut self, line: &[u8]) -> Result<Option<Telegram>, Error> {
+		match self.framer.push_line(line)? {
+			FramedLine::Ignored => {}
+			FramedLine::Header { ident, truncated } => {
+				self.pending_line.clear();
+				let ident = match String::from_utf8(ident.to_vec()) {
+					Ok(ident) => ident,
+					Err(e) => {
+						self.framer.skip();
+						return Err(e.into());
+					}
+				};
+				self.telegram = Telegram {
+					ident,
+					..Telegram::default()
+				};
+				if let Some(lines_read) = truncated {
+					return Err(Error::TruncatedTelegram { lines_read });
+				}
+			}
+			FramedLine::Object => {
+				self.apply_pending_line()?;
+				self.pending_pos = self.framer.line_pos();
+				self.pending_line.extend_from_slice(line);
+			}
+			FramedLine::Continuation => {
+				self.pending_line.extend_from_slice(CRLF);
+				self.pending_line.extend_from_slice(line);
+			}
+			FramedLine::End(crc_str) => {
+				self.apply_pending_line()?;
+				let crc = self.framer.check_crc(crc_str)?;
+				self.telegram.crc_valid = crc.valid;
+				self.telegram.crc_actual = crc.actual;
+				self.telegram.crc_expected = crc.expected;
+				return Ok(Some(mem::take(&mut self.telegram)));
+			}
+		}
+		Ok(None)
+	}
+
+	fn apply_pending_line(&mut self) -> Result<(), Error> {
+		if self.pending_line.is_empty() {
+			return Ok(());
+		}
+		let res = match ParsedLine::parse(&self.pending_line) {
+			Ok(line) => match self.telegram.apply_line(&line) {
+				Err(e) if self.options.lenient => {
+					self.telegram.field_errors.push(FieldError::new(&line, &e));
+					Ok(())
+				}
+				res => res.map(|_| ()),
+			},
+			Err(e) => {
+				// the lines that aren't valid objects are skipped, the lenient mode also reports the ones with the OBIS code
+				match ObisCode::parse_prefix(&self.pending_line) {
+					Some((obis, values)) if self.options.lenient => self.telegram.field_errors.push(FieldError {
+						obis,
+						values: vec![String::from_utf8_lossy(values).into_owned()],
+						message: e.to_string(),
+					}),
+					_ => warn!("Skipping the malformed line: {}", String::from_utf8_lossy(&self.pending_line)),
+				}
+				Ok(())
+			}
+		};
+		let (line_number, offset) = self.pending_pos;
+		let res = res.map_err(|e| e.in_line(&self.pending_line, line_number, offset));
+		self.pending_line.clear();
+		res
+	}
+}
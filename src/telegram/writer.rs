@@ -0,0 +1,278 @@
+use alloc::string::String;
+use core::fmt;
+use core::fmt::Write;
+#[cfg(feature = "std")]
+use std::io;
+
+use super::Telegram;
+use crate::{ObisCode, UnitValue};
+
+impl Telegram {
+	/// Write the telegram in the DSMR 5 format with the correct CRC
+	///
+	/// See [Display](#impl-Display-for-Telegram) implementation for details.
+	#[cfg(feature = "std")]
+	pub fn write_to(&self, mut dst: impl io::Write) -> io::Result<()> {
+		write!(dst, "{self}")
+	}
+
+	/// Object lines of the telegram without the header and the CRC line
+	pub(crate) fn object_lines(&self) -> String {
+		let mut out = ObjectWriter(String::with_capacity(1024));
+		// writing to a String can't fail
+		let _ = self.write_objects(&mut out);
+		out.0
+	}
+
+	fn write_objects(&self, out: &mut ObjectWriter) -> fmt::Result {
+		if let Some(version) = &self.version {
+			out.line(ObisCode::VERSION, &[version])?;
+		}
+		if let Some(date) = &self.electricity_date {
+			out.line(ObisCode::ELECTRICITY_DATE, &[date])?;
+		}
+		if let Some(id) = &self.electricity_equipment_id {
+			out.line(ObisCode::ELECTRICITY_EQUIPMENT_ID, &[&OctetString(id)])?;
+		}
+		for (obis, value) in [
+			(ObisCode::ELECTRICITY_CONSUMED_TARIFF_1, &self.electricity_consumed_tariff_1),
+			(ObisCode::ELECTRICITY_CONSUMED_TARIFF_2, &self.electricity_consumed_tariff_2),
+			(ObisCode::ELECTRICITY_GENERATED_TARIFF_1, &self.electricity_generated_tariff_1),
+			(ObisCode::ELECTRICITY_GENERATED_TARIFF_2, &self.electricity_generated_tariff_2),
+		] {
+			if let Some(value) = value {
+				out.line(obis, &[&Fixed(value, 10, 3)])?;
+			}
+		}
+		if let Some(tariff) = &self.current_tariff {
+			out.line(ObisCode::CURRENT_TARIFF, &[&OctetString(tariff)])?;
+		}
+		if let Some(power) = &self.power {
+			out.line(ObisCode::POWER, &[&Fixed(power, 6, 3)])?;
+		}
+		if let Some(power) = &self.return_power {
+			out.line(ObisCode::RETURN_POWER, &[&Fixed(power, 6, 3)])?;
+		}
+		if let Some(count) = self.power_failure_count {
+			out.line(ObisCode::POWER_FAILURE_COUNT, &[&format_args!("{count:05}")])?;
+		}
+		if let Some(count) = self.long_power_failure_count {
+			out.line(ObisCode::LONG_POWER_FAILURE_COUNT, &[&format_args!("{count:05}")])?;
+		}
+		// the power failure log is mandatory since DSMR 4.0, even if it's empty
+		if !self.power_failure_log.is_empty() || self.version.is_some() {
+			out.start(ObisCode::POWER_FAILURE_LOG)?;
+			out.value(self.power_failure_log.len())?;
+			out.value(ObisCode::POWER_FAILURE_DURATION)?;
+			for entry in &self.power_failure_log {
+				out.value(entry.end_date)?;
+				out.value(Fixed(&entry.duration, 10, 0))?;
+			}
+			out.end();
+		}
+		for (obis, count) in [
+			(ObisCode::VOLTAGE_SAG_L1_COUNT, self.voltage_sag_l1_count),
+			(ObisCode::VOLTAGE_SAG_L2_COUNT, self.voltage_sag_l2_count),
+			(ObisCode::VOLTAGE_SAG_L3_COUNT, self.voltage_sag_l3_count),
+			(ObisCode::VOLTAGE_SWELL_L1_COUNT, self.voltage_swell_l1_count),
+			(ObisCode::VOLTAGE_SWELL_L2_COUNT, self.voltage_swell_l2_count),
+			(ObisCode::VOLTAGE_SWELL_L3_COUNT, self.voltage_swell_l3_count),
+		] {
+			if let Some(count) = count {
+				out.line(obis, &[&format_args!("{count:05}")])?;
+			}
+		}
+		if let Some(message) = &self.message {
+			out.line(ObisCode::MESSAGE, &[&OctetString(message)])?;
+		}
+		for (obis, voltage) in [
+			(ObisCode::VOLTAGE_L1, &self.voltage_l1),
+			(ObisCode::VOLTAGE_L2, &self.voltage_l2),
+			(ObisCode::VOLTAGE_L3, &self.voltage_l3),
+		] {
+			if let Some(voltage) = voltage {
+				out.line(obis, &[&Fixed(voltage, 5, 1)])?;
+			}
+		}
+		for (obis, current) in [
+			(ObisCode::CURRENT_L1, &self.current_l1),
+			(ObisCode::CURRENT_L2, &self.current_l2),
+			(ObisCode::CURRENT_L3, &self.current_l3),
+		] {
+			if let Some(current) = current {
+				out.line(obis, &[&Fixed(current, 3, 0)])?;
+			}
+		}
+		for (obis, power) in [
+			(ObisCode::POWER_L1, &self.power_l1),
+			(ObisCode::POWER_L2, &self.power_l2),
+			(ObisCode::POWER_L3, &self.power_l3),
+			(ObisCode::RETURN_POWER_L1, &self.return_power_l1),
+			(ObisCode::RETURN_POWER_L2, &self.return_power_l2),
+			(ObisCode::RETURN_POWER_L3, &self.return_power_l3),
+		] {
+			if let Some(power) = power {
+				out.line(obis, &[&Fixed(power, 6, 3)])?;
+			}
+		}
+		if let Some(version) = &self.emucs_version {
+			out.line(ObisCode::EMUCS_VERSION, &[version])?;
+		}
+		if let Some(demand) = &self.current_average_demand {
+			out.line(ObisCode::CURRENT_AVERAGE_DEMAND, &[&Fixed(demand, 6, 3)])?;
+		}
+		if let Some(peak) = &self.maximum_demand_month {
+			out.line(ObisCode::MAXIMUM_DEMAND_MONTH, &[&peak.date, &Fixed(&peak.demand, 6, 3)])?;
+		}
+		if !self.maximum_demand_history.is_empty() {
+			out.start(ObisCode::MAXIMUM_DEMAND_HISTORY)?;
+			out.value(self.maximum_demand_history.len())?;
+			out.value(ObisCode::MAXIMUM_DEMAND_MONTH)?;
+			out.value(ObisCode::MAXIMUM_DEMAND_MONTH)?;
+			for entry in &self.maximum_demand_history {
+				out.value(entry.month)?;
+				out.value(entry.date)?;
+				out.value(Fixed(&entry.demand, 6, 3))?;
+			}
+			out.end();
+		}
+		if let Some(state) = self.breaker_state {
+			out.line(ObisCode::BREAKER_STATE, &[&state])?;
+		}
+		if let Some(threshold) = &self.limiter_threshold {
+			out.line(ObisCode::LIMITER_THRESHOLD, &[&Fixed(threshold, 5, 1)])?;
+		}
+		if let Some(threshold) = &self.fuse_supervision_threshold {
+			out.line(ObisCode::FUSE_SUPERVISION_THRESHOLD, &[&Fixed(threshold, 3, 0)])?;
+		}
+		for device in &self.mbus_devices {
+			if let Some(device_type) = device.device_type {
+				let code = u8::from(device_type);
+				out.line(ObisCode::mbus_device_type(device.channel), &[&format_args!("{code:03}")])?;
+			}
+			if let Some(id) = &device.equipment_id {
+				out.line(ObisCode::mbus_equipment_id(device.channel), &[&OctetString(id)])?;
+			}
+			if let Some(position) = device.valve_position {
+				out.line(ObisCode::mbus_valve_position(device.channel), &[&position])?;
+			}
+			if let Some(value) = &device.capture_value {
+				let date: &dyn fmt::Display = match &device.capture_date {
+					Some(date) => date,
+					None => &"",
+				};
+				out.line(ObisCode::mbus_capture(device.channel), &[date, &Fixed(value, 9, 3)])?;
+			}
+		}
+		// the objects not decoded into the fields above are written as is, except for the ones that failed to decode
+		for object in &self.objects {
+			if !object.decoded && !self.field_errors.iter().any(|error| error.obis == object.obis) {
+				out.start(object.obis)?;
+				for value in &object.values {
+					out.value(value)?;
+				}
+				out.end();
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Writes the telegram in the DSMR 5 format with the correct CRC
+///
+/// The lines are written for every populated field using the OBIS codes that the parser decodes them from, and the numbers
+/// are formatted according to the specification, e.g. `1-0:1.8.1(004169.415*kWh)`. The objects from [Telegram::objects]
+/// that aren't [decoded](super::CosemObject::decoded) into any field are written after them as is, while the ones that
+/// failed to decode in the lenient mode (listed in [Telegram::field_errors]) are left out, so that the output passes the
+/// strict parse. The power failure log is written even if it's empty when the telegram has the version, as it's mandatory
+/// since DSMR 4.0.
+///
+/// Reading the output back gives a telegram with the same fields according to [Telegram::fields_eq], the raw objects and
+/// the CRC data are filled in by the parser.
+///
+/// ```
+/// use dsmr_parse::{Telegram, TelegramDecoder, UnitValue};
+///
+/// let telegram = Telegram {
+///     ident: "XMX5LGBBFG1009394887".to_string(),
+///     version: Some("50".to_string()),
+///     electricity_consumed_tariff_1: Some(UnitValue::with_unit(4169.415, "kWh")),
+///     ..Telegram::default()
+/// };
+/// let message = telegram.to_string();
+/// assert_eq!(
+///     "/XMX5LGBBFG1009394887\r\n\r\n1-3:0.2.8(50)\r\n1-0:1.8.1(004169.415*kWh)\r\n1-0:99.97.0(0)(0-0:96.7.19)\r\n!068E\r\n",
+///     message
+/// );
+///
+/// let mut decoder = TelegramDecoder::new();
+/// let parsed = decoder.feed(message.as_bytes()).pop().unwrap().unwrap();
+/// assert!(telegram.fields_eq(&parsed));
+/// ```
+impl fmt::Display for Telegram {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let mut message = String::with_capacity(1024);
+		write!(message, "/{}\r\n\r\n{}!", self.ident, self.object_lines())?;
+		let crc = crc16::State::<crc16::ARC>::calculate(message.as_bytes());
+		write!(f, "{message}{crc:04X}\r\n")
+	}
+}
+
+/// Accumulates the object lines of the telegram
+struct ObjectWriter(String);
+
+impl ObjectWriter {
+	fn line(&mut self, obis: ObisCode, values: &[&dyn fmt::Display]) -> fmt::Result {
+		self.start(obis)?;
+		for value in values {
+			self.value(value)?;
+		}
+		self.end();
+		Ok(())
+	}
+
+	fn start(&mut self, obis: ObisCode) -> fmt::Result {
+		write!(self.0, "{obis}")
+	}
+
+	fn value(&mut self, value: impl fmt::Display) -> fmt::Result {
+		write!(self.0, "({value})")
+	}
+
+	fn end(&mut self) {
+		self.0.push_str("\r\n");
+	}
+}
+
+/// Number in the fixed format with the given width and number of decimals, zero padded, e.g. `004169.415*kWh`
+struct Fixed<'v, T>(&'v UnitValue<T>, usize, usize);
+
+impl<T: fmt::Display> fmt::Display for Fixed<'_, T> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let Fixed(value, width, decimals) = self;
+		write!(f, "{:0width$.decimals$}", value.value)?;
+		if let Some(unit) = &value.unit {
+			write!(f, "*{unit}")?;
+		}
+		Ok(())
+	}
+}
+
+/// Hex-encoded octet string, the counterpart of `parse_octet_string`
+pub(crate) struct OctetString<'s>(pub &'s str);
+
+impl fmt::Display for OctetString<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		for c in self.0.chars() {
+			match u8::try_from(c) {
+				Ok(byte) => write!(f, "{byte:02X}")?,
+				Err(_) => {
+					for byte in c.encode_utf8(&mut [0; 4]).bytes() {
+						write!(f, "{byte:02X}")?;
+					}
+				}
+			}
+		}
+		Ok(())
+	}
+}
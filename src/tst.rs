@@ -1,8 +1,12 @@
-use core::str;
+use core::{fmt, str};
 
 /// A point in time as reported by the meter
 ///
-/// `year` is normalized from 2 digits by mapping it to 1969..=2068 range
+/// `year` is normalized from 2 digits by mapping it to 1969..=2068 range. The legacy (DSMR 2.2/3.0) timestamps lack the DST
+/// indicator, in that case `dst` is `false`.
+///
+/// With the `serde` feature it's serialized as the local time in the ISO 8601 format along with the DST flag, e.g.
+/// `{"datetime":"2019-01-01T12:54:31","dst":false}`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Tst {
 	/// Year, normalized from 2 digits by mapping it to 1969..=2068 range
@@ -25,7 +29,7 @@ impl Tst {
 		let hour = str::from_utf8(parts.next()?).ok()?.parse().ok()?;
 		let minute = str::from_utf8(parts.next()?).ok()?.parse().ok()?;
 		let second = str::from_utf8(parts.next()?).ok()?.parse().ok()?;
-		let dst = parts.next()? == b"S";
+		let dst = parts.next() == Some(b"S");
 		Some(Self {
 			year,
 			month,
@@ -37,6 +41,37 @@ impl Tst {
 		})
 	}
 
+	/// Local date and time in the ISO 8601 format without the offset, e.g. `2019-01-01T12:54:31`
+	#[cfg(any(feature = "serde", feature = "csv"))]
+	pub(crate) fn iso_local(&self) -> impl fmt::Display {
+		IsoLocal(*self)
+	}
+
+	/// Date and time in the RFC 3339 format with the offset of the Central European Time, `+02:00` if DST is active
+	///
+	/// The legacy timestamps without the DST flag always get `+01:00`, even in summer.
+	#[cfg(feature = "csv")]
+	pub(crate) fn to_cet_rfc3339(self) -> alloc::string::String {
+		let offset = if self.dst {
+			2
+		} else {
+			1
+		};
+		alloc::format!("{}+{offset:02}:00", self.iso_local())
+	}
+
+	/// Parse the date and time produced by [Tst::to_cet_rfc3339]
+	#[cfg(feature = "csv")]
+	pub(crate) fn from_cet_rfc3339(s: &str) -> Option<Self> {
+		let (datetime, offset) = s.split_at_checked(s.len().checked_sub("+01:00".len())?)?;
+		let dst = match offset {
+			"+01:00" => false,
+			"+02:00" => true,
+			_ => return None,
+		};
+		parse_iso_datetime(datetime, dst)
+	}
+
 	/// Convert current [Tst] to [jiff::Zoned] in the indicated timezone
 	#[cfg(feature = "jiff")]
 	pub fn to_jiff(&self, timezone: &jiff::tz::TimeZone) -> Option<jiff::Zoned> {
@@ -80,6 +115,74 @@ impl Tst {
 	}
 }
 
+/// Formats the timestamp as in the telegram, e.g. `230309191049W`
+impl fmt::Display for Tst {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(
+			f,
+			"{:02}{:02}{:02}{:02}{:02}{:02}{}",
+			self.year % 100,
+			self.month,
+			self.day,
+			self.hour,
+			self.minute,
+			self.second,
+			if self.dst {
+				'S'
+			} else {
+				'W'
+			}
+		)
+	}
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Tst {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		use alloc::string::ToString;
+
+		use serde::ser::SerializeStruct;
+
+		let mut out = serializer.serialize_struct("Tst", 2)?;
+		out.serialize_field("datetime", &self.iso_local().to_string())?;
+		out.serialize_field("dst", &self.dst)?;
+		out.end()
+	}
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Tst {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		#[derive(serde::Deserialize)]
+		#[serde(rename = "Tst")]
+		struct Repr {
+			datetime: alloc::string::String,
+			dst: bool,
+		}
+
+		let Repr { datetime, dst } = Repr::deserialize(deserializer)?;
+		parse_iso_datetime(&datetime, dst)
+			.ok_or_else(|| serde::de::Error::custom(format_args!("Invalid ISO 8601 date and time: {datetime}")))
+	}
+}
+
+/// Parse the local date and time in the `YYYY-MM-DDThh:mm:ss` format
+#[cfg(any(feature = "serde", feature = "csv"))]
+fn parse_iso_datetime(s: &str, dst: bool) -> Option<Tst> {
+	let (date, time) = s.split_once('T')?;
+	let mut date = date.splitn(3, '-');
+	let mut time = time.splitn(3, ':');
+	Some(Tst {
+		year: date.next()?.parse().ok()?,
+		month: date.next()?.parse().ok()?,
+		day: date.next()?.parse().ok()?,
+		hour: time.next()?.parse().ok()?,
+		minute: time.next()?.parse().ok()?,
+		second: time.next()?.parse().ok()?,
+		dst,
+	})
+}
+
 fn normalize_two_digit_year(year: u16) -> u16 {
 	if (69..=99).contains(&year) {
 		year + 1900
@@ -89,3 +192,23 @@ fn normalize_two_digit_year(year: u16) -> u16 {
 		year
 	}
 }
+
+/// [Tst] formatted as the local date and time in the ISO 8601 format, see [Tst::iso_local]
+#[cfg(any(feature = "serde", feature = "csv"))]
+struct IsoLocal(Tst);
+
+#[cfg(any(feature = "serde", feature = "csv"))]
+impl fmt::Display for IsoLocal {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let Tst {
+			year,
+			month,
+			day,
+			hour,
+			minute,
+			second,
+			..
+		} = self.0;
+		write!(f, "{year:04}-{month:02}-{day:02}T{hour:02}:{minute:02}:{second:02}")
+	}
+}
@@ -1,12 +1,17 @@
+use alloc::string::{String, ToString};
+use core::ops::Deref;
 use core::str;
-use std::ops::Deref;
-use std::str::FromStr;
+use core::str::FromStr;
 
 /// A single value with an optional unit attached to it
+///
+/// The unit is an owned [String] by default, [UnitValue::parse_borrowed] produces a value with the unit borrowed from the
+/// input.
 #[derive(Debug, Default, PartialEq, PartialOrd, Clone)]
-pub struct UnitValue<T> {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UnitValue<T, U = String> {
 	pub value: T,
-	pub unit: Option<String>,
+	pub unit: Option<U>,
 }
 
 impl<T> UnitValue<T> {
@@ -22,7 +27,26 @@ impl<T> UnitValue<T> {
 	}
 }
 
-impl<T> Deref for UnitValue<T> {
+impl<'u, T: FromStr> UnitValue<T, &'u str> {
+	/// Parse the value from a string like `123.4*kWh` keeping the unit borrowed from `s`
+	pub fn parse_borrowed(s: &'u str) -> Result<Self, T::Err> {
+		let (value, unit) = split_unit(s);
+		Ok(Self {
+			value: T::from_str(value)?,
+			unit,
+		})
+	}
+
+	/// Convert to the [UnitValue] with an owned unit
+	pub fn into_owned(self) -> UnitValue<T> {
+		UnitValue {
+			value: self.value,
+			unit: self.unit.map(str::to_string),
+		}
+	}
+}
+
+impl<T, U> Deref for UnitValue<T, U> {
 	type Target = T;
 
 	fn deref(&self) -> &Self::Target {
@@ -34,10 +58,14 @@ impl<T: FromStr> FromStr for UnitValue<T> {
 	type Err = T::Err;
 
 	fn from_str(s: &str) -> Result<Self, Self::Err> {
-		let (value, unit) = s.split_once('*').map_or((s, None), |(val, unit)| (val, Some(unit)));
+		let (value, unit) = split_unit(s);
 		Ok(Self {
 			value: T::from_str(value)?,
 			unit: unit.map(str::to_string),
 		})
 	}
 }
+
+fn split_unit(s: &str) -> (&str, Option<&str>) {
+	s.split_once('*').map_or((s, None), |(val, unit)| (val, Some(unit)))
+}
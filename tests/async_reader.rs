@@ -0,0 +1,52 @@
+#![cfg(feature = "tokio")]
+
+use dsmr_parse::{Error, Telegram, TelegramStream};
+use futures_util::StreamExt;
+use matches::assert_matches;
+
+mod common;
+
+#[tokio::test]
+async fn test_read_from_async() {
+	let message = include_bytes!("telegram.txt");
+	let tgrm = Telegram::read_from_async(message.as_slice()).await.unwrap().unwrap();
+	assert_eq!(Telegram::read_from(message.as_slice()).unwrap().unwrap(), tgrm);
+
+	let tgrm = Telegram::read_from_async(b"Garbage" as &[u8]).await;
+	assert_matches!(tgrm, Ok(None));
+}
+
+#[tokio::test]
+async fn test_read_from_async_consecutive() {
+	let message = [include_bytes!("telegram2.txt").as_slice(), include_bytes!("telegram3.txt")].concat();
+	let mut src = message.as_slice();
+	let tgrm = Telegram::read_from_async(&mut src).await.unwrap().unwrap();
+	assert_eq!("ISK5\\2M550T-4567", tgrm.ident);
+	let tgrm = Telegram::read_from_async(&mut src).await.unwrap().unwrap();
+	assert_eq!("ISk5\\2MT382-1000", tgrm.ident);
+	assert_matches!(Telegram::read_from_async(&mut src).await, Ok(None));
+}
+
+#[tokio::test]
+async fn test_stream() {
+	let corrupted = common::corrupted_telegram();
+	let message = [
+		include_bytes!("telegram2.txt").as_slice(),
+		corrupted.as_bytes(),
+		include_bytes!("telegram3.txt"),
+	]
+	.concat();
+	let mut stream = TelegramStream::new(message.as_slice());
+	assert_matches!(stream.next().await, Some(Ok(tgrm)) if tgrm.ident == "ISK5\\2M550T-4567");
+	assert_matches!(stream.next().await, Some(Err(Error::CrcMismatch(..))));
+	assert_matches!(stream.next().await, Some(Ok(tgrm)) if tgrm.ident == "ISk5\\2MT382-1000");
+	assert_matches!(stream.next().await, None);
+}
+
+#[tokio::test]
+async fn test_stream_into_inner() {
+	let message = [include_bytes!("telegram2.txt").as_slice(), b"rest"].concat();
+	let mut stream = TelegramStream::new(message.as_slice());
+	assert_matches!(stream.next().await, Some(Ok(_)));
+	assert_eq!(b"rest", stream.into_inner());
+}
@@ -0,0 +1,20 @@
+//! Fixtures shared by the integration tests
+
+// every test crate includes the module, but not all of them use every fixture
+#![allow(dead_code)]
+
+/// All sample telegrams: DSMR 5 ones, the legacy DSMR 2.2 one without the CRC and the Belgian e-MUCS one
+pub const TELEGRAMS: [&[u8]; 5] = [
+	include_bytes!("../telegram.txt"),
+	include_bytes!("../telegram2.txt"),
+	include_bytes!("../telegram3.txt"),
+	include_bytes!("../telegram_v22.txt"),
+	include_bytes!("../telegram_be.txt"),
+];
+
+/// The first sample telegram with a value changed, so that its CRC doesn't match
+pub fn corrupted_telegram() -> String {
+	String::from_utf8(include_bytes!("../telegram.txt").to_vec())
+		.unwrap()
+		.replace("004169.415", "004169.416")
+}
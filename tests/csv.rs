@@ -0,0 +1,152 @@
+#![cfg(all(feature = "csv", feature = "std"))]
+
+use dsmr_parse::{CrcMode, CsvReader, CsvWriter, Error, ObisCode, ParseOptions, Telegram, TelegramReader, UnitValue};
+
+mod common;
+
+fn telegrams() -> Vec<Telegram> {
+	common::TELEGRAMS
+		.into_iter()
+		.map(|src| Telegram::read_from(src).unwrap().unwrap())
+		.collect()
+}
+
+fn write_csv(telegrams: &[Telegram]) -> Vec<u8> {
+	let mut writer = CsvWriter::new(vec![]);
+	for telegram in telegrams {
+		writer.write(telegram).unwrap();
+	}
+	writer.into_inner().unwrap()
+}
+
+/// Telegram without the values that aren't stored in the CSV
+fn comparable(telegram: &Telegram) -> Telegram {
+	let mut mbus_devices = telegram.mbus_devices.clone();
+	for device in &mut mbus_devices {
+		device.equipment_id = device.equipment_id.take().filter(|id| !id.is_empty());
+	}
+	Telegram {
+		mbus_devices,
+		objects: vec![],
+		crc_expected: None,
+		crc_actual: 0,
+		..telegram.clone()
+	}
+}
+
+#[test]
+fn test_round_trip() {
+	let telegrams = telegrams();
+	let csv = write_csv(&telegrams);
+	let read = CsvReader::new(csv.as_slice())
+		.unwrap()
+		.collect::<Result<Vec<_>, _>>()
+		.unwrap();
+	assert_eq!(telegrams.len(), read.len());
+	for (expected, actual) in telegrams.iter().zip(&read) {
+		assert_eq!(comparable(expected), comparable(actual));
+	}
+}
+
+#[test]
+fn test_columns() {
+	let telegrams = telegrams();
+	let csv = String::from_utf8(write_csv(&telegrams[..1])).unwrap();
+	let mut lines = csv.lines();
+	let header = lines.next().unwrap().split(',').collect::<Vec<_>>();
+	let row = lines.next().unwrap().split(',').collect::<Vec<_>>();
+	assert_eq!(None, lines.next());
+	let cell = |column: &str| row[header.iter().position(|name| *name == column).unwrap()];
+
+	assert_eq!("ident", header[0]);
+	assert_eq!("4169.415", cell("electricity_consumed_tariff_1 [kWh]"));
+	assert_eq!("2019-01-01T12:54:31+01:00", cell("electricity_date"));
+	assert_eq!("0001", cell("current_tariff"));
+	assert_eq!("3", cell("mbus_1_device_type"));
+	assert_eq!("m3", cell("mbus_1_unit"));
+	assert!(header.contains(&"1-0:99.97.0"));
+	assert!(!header.contains(&"1-0:1.8.1"));
+	assert!(!header.contains(&"0-1:24.2.1"));
+	assert!(header.contains(&"power [kW]"));
+	assert_eq!("objects", header[header.len() - 1]);
+}
+
+#[test]
+fn test_units() {
+	let telegrams = [
+		Telegram {
+			power: Some(UnitValue::with_unit(1500.0, "W")),
+			voltage_l1: Some(UnitValue::without_unit(230.1)),
+			..Telegram::default()
+		},
+		Telegram {
+			power: Some(UnitValue::with_unit(1.5, "kW")),
+			voltage_l1: Some(UnitValue::with_unit(229.8, "V")),
+			current_l1: Some(UnitValue::without_unit(7)),
+			..Telegram::default()
+		},
+	];
+	let csv = String::from_utf8(write_csv(&telegrams)).unwrap();
+	let mut lines = csv.lines();
+	let header = lines.next().unwrap();
+	assert!(header.contains(",power [W],"));
+	assert!(header.contains(",voltage_l1,"));
+	assert!(header.contains(",current_l1 [A],"));
+	assert!(lines.nth(1).unwrap().contains(",1.5*kW,"));
+
+	let read = CsvReader::new(csv.as_bytes())
+		.unwrap()
+		.collect::<Result<Vec<_>, _>>()
+		.unwrap();
+	assert_eq!(telegrams.as_slice(), read.as_slice());
+}
+
+#[test]
+fn test_field_errors() {
+	let message = "/XMX5LGBBFG1009394887\r\n\r\n1-3:0.2.8(50)\r\n1-0:31.7.0(4.5*A)\r\n1-0:51.7.0(002*A)\r\n!0000\r\n";
+	let options = ParseOptions {
+		lenient: true,
+		crc: CrcMode::Ignore,
+	};
+	let telegram = TelegramReader::with_options(message.as_bytes(), options)
+		.next()
+		.unwrap()
+		.unwrap();
+	assert_eq!(1, telegram.field_errors.len());
+	let csv = write_csv(&[telegram.clone(), telegram.clone()]);
+	let csv_text = String::from_utf8(csv.clone()).unwrap();
+	assert!(csv_text.lines().next().unwrap().contains(",1-0:31.7.0,"));
+	let read = CsvReader::new(csv.as_slice())
+		.unwrap()
+		.collect::<Result<Vec<_>, _>>()
+		.unwrap();
+	for read in read {
+		assert_eq!(None, read.current_l1);
+		assert_eq!(Some(UnitValue::with_unit(2, "A")), read.current_l2);
+		assert_eq!(telegram.field_errors, read.field_errors);
+		assert_eq!(["4.5*A"], read.get(ObisCode::CURRENT_L1).unwrap().values.as_slice());
+	}
+}
+
+#[test]
+fn test_invalid_value() {
+	let csv = "ident,power\nXMX5LGBBFG1009394887,abc\n";
+	let mut reader = CsvReader::new(csv.as_bytes()).unwrap();
+	match reader.next() {
+		Some(Err(Error::InvalidCsvValue { column, value })) => {
+			assert_eq!("power", column);
+			assert_eq!("abc", value);
+		}
+		other => panic!("Unexpected result: {other:?}"),
+	}
+	assert!(reader.next().is_none());
+}
+
+#[test]
+fn test_unknown_columns() {
+	let csv = "ident,comment,power,0-0:96.13.0\nXMX5LGBBFG1009394887,anything,1.5,(303132)\n";
+	let telegram = CsvReader::new(csv.as_bytes()).unwrap().next().unwrap().unwrap();
+	assert_eq!("XMX5LGBBFG1009394887", telegram.ident);
+	assert_eq!(1.5, telegram.power.unwrap().value);
+	assert_eq!(Some("012".to_string()), telegram.message);
+}
@@ -0,0 +1,66 @@
+use dsmr_parse::{Error, MAX_LINE_LEN, TelegramDecoder, UnitValue};
+use matches::assert_matches;
+
+mod common;
+
+#[test]
+fn test_decoder_chunks() {
+	let message = [
+		include_bytes!("telegram.txt").as_slice(),
+		include_bytes!("telegram2.txt"),
+		include_bytes!("telegram3.txt"),
+	]
+	.concat();
+	for chunk_size in [1, 7, 64, 1000] {
+		let mut decoder = TelegramDecoder::new();
+		let telegrams = message
+			.chunks(chunk_size)
+			.flat_map(|chunk| decoder.feed(chunk))
+			.collect::<Result<Vec<_>, _>>()
+			.unwrap();
+		assert_eq!(3, telegrams.len());
+		assert_eq!("ISK5\\2M550T-4567", telegrams[1].ident);
+		assert_eq!(
+			Some(UnitValue::with_unit(0.595, "m3")),
+			telegrams[1].mbus_devices[0].capture_value
+		);
+	}
+}
+
+#[test]
+fn test_decoder_decode() {
+	let corrupted = common::corrupted_telegram();
+	let message = [corrupted.as_bytes(), include_bytes!("telegram3.txt")].concat();
+	let mut decoder = TelegramDecoder::new();
+	let (consumed, res) = decoder.decode(&message);
+	assert_eq!(corrupted.len(), consumed);
+	assert_matches!(res, Some(Err(Error::CrcMismatch(..))));
+	let (consumed, res) = decoder.decode(&message[consumed..]);
+	assert_eq!(include_bytes!("telegram3.txt").len(), consumed);
+	assert_matches!(res, Some(Ok(..)));
+}
+
+#[test]
+fn test_decoder_line_too_long() {
+	let mut decoder = TelegramDecoder::new();
+	assert!(decoder.feed(b"/XMX5LGBBFG1009394887\r\n\r\n0-0:96.13.0(").is_empty());
+	for _ in 0..10 {
+		assert!(decoder.feed(&[b'3'; 1000]).is_empty());
+	}
+	let mut res = decoder.feed(b")\r\n!0000\r\n");
+	assert_eq!(1, res.len());
+	assert_matches!(res.pop(), Some(Err(Error::Line(e))) if e.line.len() == MAX_LINE_LEN + 1 && matches!(e.error, Error::LineTooLong));
+	assert_matches!(decoder.feed(include_bytes!("telegram.txt")).pop(), Some(Ok(..)));
+}
+
+#[test]
+fn test_decoder_finish() {
+	let mut decoder = TelegramDecoder::new();
+	assert_matches!(decoder.finish(), None);
+	assert!(decoder.feed(b"1-0:2.7.0(00.000*kW)\r\n!1234\r\n").is_empty());
+	assert!(decoder.feed(&include_bytes!("telegram.txt")[..200]).is_empty());
+	assert_eq!(29, decoder.skipped_bytes());
+	assert_matches!(decoder.finish(), Some(Err(Error::TruncatedTelegram { lines_read: 8 })));
+	assert_matches!(decoder.finish(), None);
+	assert_matches!(decoder.feed(include_bytes!("telegram.txt")).pop(), Some(Ok(..)));
+}
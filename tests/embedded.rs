@@ -0,0 +1,48 @@
+#![cfg(feature = "embedded-io")]
+
+use dsmr_parse::{EmbeddedTelegramReader, Error, Telegram};
+use matches::assert_matches;
+
+mod common;
+
+fn corrupted_message() -> Vec<u8> {
+	let corrupted = common::corrupted_telegram();
+	[
+		include_bytes!("telegram2.txt").as_slice(),
+		corrupted.as_bytes(),
+		include_bytes!("telegram3.txt"),
+	]
+	.concat()
+}
+
+#[test]
+fn test_read_from_embedded() {
+	let tgrm = Telegram::read_from_embedded(include_bytes!("telegram3.txt").as_slice())
+		.unwrap()
+		.unwrap();
+	assert_eq!("ISk5\\2MT382-1000", tgrm.ident);
+
+	let message = corrupted_message();
+	let mut reader = EmbeddedTelegramReader::new(message.as_slice());
+	assert_matches!(reader.next(), Some(Ok(tgrm)) if tgrm.ident == "ISK5\\2M550T-4567");
+	assert_matches!(reader.next(), Some(Err(Error::CrcMismatch(..))));
+	assert_matches!(reader.next(), Some(Ok(tgrm)) if tgrm.ident == "ISk5\\2MT382-1000");
+	assert_matches!(reader.next(), None);
+}
+
+#[cfg(feature = "embedded-io-async")]
+#[tokio::test]
+async fn test_read_from_embedded_async() {
+	let tgrm = Telegram::read_from_embedded_async(include_bytes!("telegram3.txt").as_slice())
+		.await
+		.unwrap()
+		.unwrap();
+	assert_eq!("ISk5\\2MT382-1000", tgrm.ident);
+
+	let message = corrupted_message();
+	let mut reader = EmbeddedTelegramReader::new(message.as_slice());
+	assert_matches!(reader.next_async().await, Some(Ok(tgrm)) if tgrm.ident == "ISK5\\2M550T-4567");
+	assert_matches!(reader.next_async().await, Some(Err(Error::CrcMismatch(..))));
+	assert_matches!(reader.next_async().await, Some(Ok(tgrm)) if tgrm.ident == "ISk5\\2MT382-1000");
+	assert_matches!(reader.next_async().await, None);
+}
@@ -0,0 +1,75 @@
+#![cfg(all(feature = "influx", feature = "std"))]
+
+use dsmr_parse::{MbusDevice, MbusDeviceType, Telegram, UnitValue};
+use jiff::tz::TimeZone;
+
+#[test]
+fn test_to_influx() {
+	let tz = TimeZone::get("Europe/Amsterdam").expect("Can't load Amsterdam timezone");
+	let tgrm = Telegram::read_from(include_bytes!("telegram.txt").as_slice())
+		.unwrap()
+		.unwrap();
+	assert_eq!(
+		concat!(
+			"p1,equipment_id=E0034567890123456,tariff=1 consumed_tariff_1=4169.415,consumed_tariff_2=4884.452,",
+			"generated_tariff_1=0,generated_tariff_2=0,power=0.741,return_power=0,power_l1=0.741,return_power_l1=0,",
+			"current_l1=4i,power_failure_count=2i,long_power_failure_count=0i,voltage_sag_l1_count=0i,",
+			"voltage_swell_l1_count=0i 1546343671000000000\n",
+			"p1,equipment_id=G0062749506872590,channel=1,device_type=3 value=1619.203 1546340400000000000\n",
+		),
+		tgrm.to_influx("p1", &tz)
+	);
+
+	let tgrm = Telegram::read_from(include_bytes!("telegram_be.txt").as_slice())
+		.unwrap()
+		.unwrap();
+	let lines = tgrm.to_influx("p1", &tz);
+	let lines = lines.lines().collect::<Vec<_>>();
+	assert_eq!(3, lines.len());
+	assert!(lines[0].ends_with(" 1589284449000000000"));
+	assert!(lines[0].contains(",voltage_l1=234.7,voltage_l2=234.7,voltage_l3=234.7,"));
+	assert!(lines[2].starts_with("p1,equipment_id=8SET0000000000,channel=2,device_type=7 value=872.234 "));
+
+	// the empty equipment id of the M-Bus device isn't a valid tag value
+	let tgrm = Telegram::read_from(include_bytes!("telegram2.txt").as_slice())
+		.unwrap()
+		.unwrap();
+	let lines = tgrm.to_influx("p1", &tz);
+	assert!(!lines.contains("=,") && !lines.contains("= "));
+	assert!(lines.lines().nth(1).unwrap().starts_with("p1,channel=1,device_type=3 value="));
+}
+
+#[test]
+fn test_to_influx_tariff() {
+	for message in [
+		"/XMX5LGBBFG1009394887\r\n\r\n1-3:0.2.8(50)\r\n0-0:96.14.0(0002)\r\n1-0:1.7.0(00.741*kW)\r\n!C583\r\n",
+		"/XMX5LGBBFG1009394887\r\n\r\n1-3:0.2.8(50)\r\n0-0:96.14.0(30303032)\r\n1-0:1.7.0(00.741*kW)\r\n!1849\r\n",
+	] {
+		let tgrm = Telegram::read_from(message.as_bytes()).unwrap().unwrap();
+		assert_eq!("p1,tariff=2 power=0.741\n", tgrm.to_influx("p1", &TimeZone::UTC));
+	}
+	let tgrm = Telegram {
+		current_tariff: Some("0002".to_string()),
+		power: Some(UnitValue::with_unit(0.741, "kW")),
+		..Telegram::default()
+	};
+	assert_eq!("p1,tariff=2 power=0.741\n", tgrm.to_influx("p1", &TimeZone::UTC));
+}
+
+#[test]
+fn test_to_influx_escaping() {
+	let tgrm = Telegram {
+		electricity_equipment_id: Some("E 1,2=3".to_string()),
+		power: Some(UnitValue::with_unit(1.5, "kW")),
+		mbus_devices: vec![MbusDevice {
+			device_type: Some(MbusDeviceType::Gas),
+			..MbusDevice::new(1)
+		}],
+		..Telegram::default()
+	};
+	assert_eq!(
+		"my\\ p1\\,meter,equipment_id=E\\ 1\\,2\\=3 power=1.5\n",
+		tgrm.to_influx("my p1,meter", &TimeZone::UTC)
+	);
+	assert_eq!("", Telegram::default().to_influx("p1", &TimeZone::UTC));
+}
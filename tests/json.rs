@@ -0,0 +1,135 @@
+#![cfg(all(feature = "json", feature = "std"))]
+
+use dsmr_parse::{JSON_SCHEMA_VERSION, Telegram, Tst};
+use serde_json::json;
+
+#[test]
+fn test_to_json() {
+	let tgrm = Telegram::read_from(include_bytes!("telegram.txt").as_slice())
+		.unwrap()
+		.unwrap();
+	let phase = |current: f64, power: f64| {
+		json!({
+			"voltage": null,
+			"current": current,
+			"power_consumed": power,
+			"power_generated": 0.0,
+			"voltage_sags": 0,
+			"voltage_swells": 0,
+		})
+	};
+	assert_eq!(
+		json!({
+			"schema_version": JSON_SCHEMA_VERSION,
+			"ident": "XMX5LGBBFG1098765432",
+			"dsmr_version": "42",
+			"timestamp": "2019-01-01T12:54:31+01:00",
+			"equipment_id": "E0034567890123456",
+			"tariff": 1,
+			"message": "",
+			"energy": {
+				"consumed_tariff_1": 4169415.0,
+				"consumed_tariff_2": 4884452.0,
+				"generated_tariff_1": 0.0,
+				"generated_tariff_2": 0.0,
+			},
+			"power": {
+				"consumed": 741.0,
+				"generated": 0.0,
+			},
+			"phases": {
+				"l1": phase(4.0, 741.0),
+				"l2": {
+					"voltage": null,
+					"current": null,
+					"power_consumed": null,
+					"power_generated": null,
+					"voltage_sags": null,
+					"voltage_swells": null,
+				},
+				"l3": {
+					"voltage": null,
+					"current": null,
+					"power_consumed": null,
+					"power_generated": null,
+					"voltage_sags": null,
+					"voltage_swells": null,
+				},
+			},
+			"power_failures": {
+				"count": 2,
+				"long_count": 0,
+				"log": [],
+			},
+			"demand": {
+				"current_average": null,
+				"maximum_month": null,
+				"history": [],
+			},
+			"breaker_state": null,
+			"limiter_threshold": null,
+			"fuse_supervision_threshold": null,
+			"mbus": [{
+				"channel": 1,
+				"device_type": 3,
+				"equipment_id": "G0062749506872590",
+				"valve_position": null,
+				"timestamp": "2019-01-01T12:00:00+01:00",
+				"value": 1619.203,
+				"unit": "m3",
+			}],
+		}),
+		tgrm.to_json()
+	);
+}
+
+#[test]
+fn test_to_json_emucs() {
+	let tgrm = Telegram::read_from(include_bytes!("telegram_be.txt").as_slice())
+		.unwrap()
+		.unwrap();
+	let value = tgrm.to_json();
+	assert_eq!(json!("2020-05-12T13:54:09+02:00"), value["timestamp"]);
+	assert_eq!(json!(234.7), value["phases"]["l3"]["voltage"]);
+	assert_eq!(json!(999.0), value["fuse_supervision_threshold"]);
+	assert_eq!(json!(999900.0), value["limiter_threshold"]);
+	assert_eq!(json!(2351.0), value["demand"]["current_average"]);
+	assert_eq!(
+		json!({"timestamp": "2020-05-09T13:45:58+02:00", "demand": 2589.0}),
+		value["demand"]["maximum_month"]
+	);
+	assert_eq!(
+		json!({"month": "2020-03-01T00:00:00+01:00", "timestamp": "2020-02-10T03:54:21+01:00", "demand": 4318.0}),
+		value["demand"]["history"][2]
+	);
+	assert_eq!(json!(7), value["mbus"][1]["device_type"]);
+}
+
+#[test]
+fn test_to_json_timestamps() {
+	let tst = |month, day, hour, dst| Tst {
+		year: 2023,
+		month,
+		day,
+		hour,
+		minute: 30,
+		second: 0,
+		dst,
+	};
+	let timestamp = |tst| {
+		Telegram {
+			electricity_date: Some(tst),
+			..Telegram::default()
+		}
+		.to_json()["timestamp"]
+			.clone()
+	};
+	assert_eq!(json!("2023-01-10T12:30:00+01:00"), timestamp(tst(1, 10, 12, false)));
+	assert_eq!(json!("2023-07-10T12:30:00+02:00"), timestamp(tst(7, 10, 12, true)));
+	// legacy telegrams don't report DST
+	assert_eq!(json!("2023-07-10T12:30:00+02:00"), timestamp(tst(7, 10, 12, false)));
+	// the flag picks one of the ambiguous times at the end of DST
+	assert_eq!(json!("2023-10-29T02:30:00+02:00"), timestamp(tst(10, 29, 2, true)));
+	assert_eq!(json!("2023-10-29T02:30:00+01:00"), timestamp(tst(10, 29, 2, false)));
+	assert_eq!(json!(null), timestamp(tst(13, 1, 0, false)));
+}
@@ -0,0 +1,187 @@
+#![cfg(all(feature = "mqtt", feature = "std"))]
+
+use dsmr_parse::{MbusDevice, MbusDeviceType, MqttMessage, MqttOptions, MqttPublisher, Telegram, TopicLayout, UnitValue};
+use serde_json::{Value, json};
+
+fn telegram() -> Telegram {
+	Telegram::read_from(include_bytes!("telegram.txt").as_slice())
+		.unwrap()
+		.unwrap()
+}
+
+#[test]
+fn test_state_messages() {
+	let tgrm = telegram();
+	let messages = tgrm.mqtt_state_messages(&MqttOptions::default());
+	assert_eq!(
+		MqttMessage {
+			topic: "dsmr/electricity_consumed_tariff_1".to_string(),
+			payload: "4169.415".to_string(),
+			retain: false,
+		},
+		messages[0]
+	);
+	let topics = messages.iter().map(|m| m.topic.as_str()).collect::<Vec<_>>();
+	assert_eq!(
+		[
+			"dsmr/electricity_consumed_tariff_1",
+			"dsmr/electricity_consumed_tariff_2",
+			"dsmr/electricity_generated_tariff_1",
+			"dsmr/electricity_generated_tariff_2",
+			"dsmr/power",
+			"dsmr/return_power",
+			"dsmr/power_l1",
+			"dsmr/return_power_l1",
+			"dsmr/current_l1",
+			"dsmr/power_failure_count",
+			"dsmr/long_power_failure_count",
+			"dsmr/tariff",
+			"dsmr/mbus_1",
+		],
+		topics.as_slice()
+	);
+	assert_eq!("4", messages[8].payload);
+	assert_eq!("1619.203", messages[12].payload);
+
+	let options = MqttOptions {
+		base_topic: "home/p1".to_string(),
+		layout: TopicLayout::Json,
+		..MqttOptions::default()
+	};
+	let messages = tgrm.mqtt_state_messages(&options);
+	assert_eq!(1, messages.len());
+	assert_eq!("home/p1/state", messages[0].topic);
+	let state: Value = serde_json::from_str(&messages[0].payload).unwrap();
+	assert_eq!(json!(0.741), state["power"]);
+	assert_eq!(json!(1), state["tariff"]);
+	assert_eq!(json!(1619.203), state["mbus_1"]);
+}
+
+#[test]
+fn test_discovery_messages() {
+	let tgrm = telegram();
+	let messages = tgrm.mqtt_discovery_messages(&MqttOptions::default());
+	assert_eq!(13, messages.len());
+	assert!(messages.iter().all(|m| m.retain));
+	let energy = &messages[0];
+	assert_eq!(
+		"homeassistant/sensor/E0034567890123456/electricity_consumed_tariff_1/config",
+		energy.topic
+	);
+	assert_eq!(
+		json!({
+			"name": "Energy consumed tariff 1",
+			"unique_id": "E0034567890123456_electricity_consumed_tariff_1",
+			"state_topic": "dsmr/electricity_consumed_tariff_1",
+			"device_class": "energy",
+			"state_class": "total_increasing",
+			"unit_of_measurement": "kWh",
+			"device": {
+				"identifiers": ["E0034567890123456"],
+				"name": "DSMR meter",
+				"model": "XMX5LGBBFG1098765432",
+				"sw_version": "42",
+			},
+		}),
+		serde_json::from_str::<Value>(&energy.payload).unwrap()
+	);
+	let config = |key: &str| {
+		let message = messages
+			.iter()
+			.find(|m| m.topic.ends_with(&format!("/{key}/config")))
+			.unwrap();
+		serde_json::from_str::<Value>(&message.payload).unwrap()
+	};
+	let power = config("power");
+	assert_eq!(json!("power"), power["device_class"]);
+	assert_eq!(json!("measurement"), power["state_class"]);
+	assert_eq!(json!("kW"), power["unit_of_measurement"]);
+	let current = config("current_l1");
+	assert_eq!(json!("current"), current["device_class"]);
+	assert_eq!(json!("A"), current["unit_of_measurement"]);
+	let gas = config("mbus_1");
+	assert_eq!(json!("Gas 1"), gas["name"]);
+	assert_eq!(json!("gas"), gas["device_class"]);
+	assert_eq!(json!("total_increasing"), gas["state_class"]);
+	assert_eq!(json!("m³"), gas["unit_of_measurement"]);
+
+	let options = MqttOptions {
+		layout: TopicLayout::Json,
+		..MqttOptions::default()
+	};
+	let messages = tgrm.mqtt_discovery_messages(&options);
+	let gas = serde_json::from_str::<Value>(&messages[12].payload).unwrap();
+	assert_eq!(json!("dsmr/state"), gas["state_topic"]);
+	assert_eq!(json!("{{ value_json.mbus_1 }}"), gas["value_template"]);
+}
+
+#[test]
+fn test_discovery_mbus_energy() {
+	let tgrm = Telegram {
+		mbus_devices: vec![
+			MbusDevice {
+				device_type: Some(MbusDeviceType::Electricity),
+				capture_value: Some(UnitValue::with_unit(12.5, "kWh")),
+				..MbusDevice::new(1)
+			},
+			MbusDevice {
+				device_type: Some(MbusDeviceType::Heat),
+				capture_value: Some(UnitValue::without_unit(3.25)),
+				..MbusDevice::new(2)
+			},
+		],
+		..Telegram::default()
+	};
+	let configs = tgrm
+		.mqtt_discovery_messages(&MqttOptions::default())
+		.iter()
+		.map(|m| serde_json::from_str::<Value>(&m.payload).unwrap())
+		.collect::<Vec<_>>();
+	assert_eq!(2, configs.len());
+	assert_eq!(json!("Electricity 1"), configs[0]["name"]);
+	assert_eq!(json!("energy"), configs[0]["device_class"]);
+	assert_eq!(json!("total_increasing"), configs[0]["state_class"]);
+	assert_eq!(json!("kWh"), configs[0]["unit_of_measurement"]);
+	assert_eq!(json!("Heat 2"), configs[1]["name"]);
+	assert_eq!(json!("energy"), configs[1]["device_class"]);
+	assert_eq!(json!("GJ"), configs[1]["unit_of_measurement"]);
+}
+
+#[test]
+fn test_publish() {
+	/// Broker stand-in accepting a limited number of messages
+	struct Broker {
+		received: Vec<(String, String)>,
+		capacity: usize,
+	}
+
+	impl MqttPublisher for Broker {
+		type Error = String;
+
+		fn publish(&mut self, message: MqttMessage) -> Result<(), Self::Error> {
+			if self.received.len() == self.capacity {
+				return Err(format!("Queue full, dropped {}", message.topic));
+			}
+			self.received.push((message.topic, message.payload));
+			Ok(())
+		}
+	}
+
+	let tgrm = telegram();
+	let mut messages = vec![];
+	tgrm.publish_mqtt(&mut messages, &MqttOptions::default()).unwrap();
+	assert_eq!(tgrm.mqtt_state_messages(&MqttOptions::default()), messages);
+
+	let mut broker = Broker {
+		received: vec![],
+		capacity: 2,
+	};
+	assert_eq!(
+		Err("Queue full, dropped dsmr/electricity_generated_tariff_1".to_string()),
+		tgrm.publish_mqtt(&mut broker, &MqttOptions::default())
+	);
+	assert_eq!(
+		("dsmr/electricity_consumed_tariff_2".to_string(), "4884.452".to_string()),
+		broker.received[1]
+	);
+}
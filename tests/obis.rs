@@ -0,0 +1,59 @@
+use std::collections::BTreeSet;
+
+use dsmr_parse::{ObisCode, ParseObisError};
+
+#[test]
+fn test_parse() {
+	assert_eq!(Ok(ObisCode::new(1, 0, 1, 8, 1, 255)), "1-0:1.8.1".parse());
+	assert_eq!(Ok(ObisCode::VERSION), "1-3:0.2.8".parse());
+	assert_eq!(Ok(ObisCode::mbus_capture(2)), "0-2:24.2.1".parse());
+	assert_eq!(Ok(ObisCode::new(1, 0, 1, 8, 0, 101)), "1-0:1.8.0.101".parse());
+	assert_eq!(Ok(ObisCode::new(1, 0, 1, 8, 0, 101)), "1-0:1.8.0*101".parse());
+	for invalid in [
+		"",
+		"1.8.1",
+		"1-0:1.8",
+		"1-0:1.8.1.2.3",
+		"1-0:1.8.1.2*3",
+		"1-0:1.8.256",
+		"1-0:1..1",
+		"1-0:+1.8.1",
+		"a-0:1.8.1",
+		"1-0:1.8.1(",
+	] {
+		assert_eq!(Err(ParseObisError), invalid.parse::<ObisCode>(), "{invalid}");
+	}
+}
+
+#[test]
+fn test_display() {
+	assert_eq!("1-0:1.8.1", ObisCode::ELECTRICITY_CONSUMED_TARIFF_1.to_string());
+	assert_eq!("0-1:24.1.0", ObisCode::mbus_device_type(1).to_string());
+	assert_eq!("1-0:1.8.0.101", ObisCode::new(1, 0, 1, 8, 0, 101).to_string());
+	for obis in [
+		ObisCode::POWER_FAILURE_LOG,
+		ObisCode::MAXIMUM_DEMAND_HISTORY,
+		ObisCode::new(7, 4, 0, 0, 0, 0),
+	] {
+		assert_eq!(Ok(obis), obis.to_string().parse());
+	}
+}
+
+#[test]
+fn test_ord() {
+	let codes = BTreeSet::from([
+		ObisCode::VOLTAGE_L1,
+		ObisCode::mbus_equipment_id(1),
+		ObisCode::ELECTRICITY_CONSUMED_TARIFF_2,
+		ObisCode::ELECTRICITY_CONSUMED_TARIFF_1,
+	]);
+	assert_eq!(
+		vec![
+			ObisCode::mbus_equipment_id(1),
+			ObisCode::ELECTRICITY_CONSUMED_TARIFF_1,
+			ObisCode::ELECTRICITY_CONSUMED_TARIFF_2,
+			ObisCode::VOLTAGE_L1,
+		],
+		codes.into_iter().collect::<Vec<_>>()
+	);
+}
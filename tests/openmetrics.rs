@@ -0,0 +1,106 @@
+#![cfg(all(feature = "openmetrics", feature = "std"))]
+
+use dsmr_parse::{MbusDevice, Telegram, UnitValue};
+
+#[test]
+fn test_to_openmetrics() {
+	let tgrm = Telegram::read_from(include_bytes!("telegram.txt").as_slice())
+		.unwrap()
+		.unwrap();
+	assert_eq!(
+		r#"# TYPE dsmr_meter info
+# HELP dsmr_meter Meter identification
+dsmr_meter_info{equipment_id="E0034567890123456",ident="XMX5LGBBFG1098765432",version="42"} 1
+# TYPE dsmr_electricity_consumed_kilowatt_hours counter
+# UNIT dsmr_electricity_consumed_kilowatt_hours kilowatt_hours
+# HELP dsmr_electricity_consumed_kilowatt_hours Electricity delivered to the client
+dsmr_electricity_consumed_kilowatt_hours_total{equipment_id="E0034567890123456",tariff="1"} 4169.415
+dsmr_electricity_consumed_kilowatt_hours_total{equipment_id="E0034567890123456",tariff="2"} 4884.452
+# TYPE dsmr_electricity_generated_kilowatt_hours counter
+# UNIT dsmr_electricity_generated_kilowatt_hours kilowatt_hours
+# HELP dsmr_electricity_generated_kilowatt_hours Electricity delivered by the client
+dsmr_electricity_generated_kilowatt_hours_total{equipment_id="E0034567890123456",tariff="1"} 0
+dsmr_electricity_generated_kilowatt_hours_total{equipment_id="E0034567890123456",tariff="2"} 0
+# TYPE dsmr_tariff gauge
+# HELP dsmr_tariff Current tariff indicator
+dsmr_tariff{equipment_id="E0034567890123456"} 1
+# TYPE dsmr_power_consumed_kilowatts gauge
+# UNIT dsmr_power_consumed_kilowatts kilowatts
+# HELP dsmr_power_consumed_kilowatts Actual power delivered to the client
+dsmr_power_consumed_kilowatts{equipment_id="E0034567890123456"} 0.741
+# TYPE dsmr_phase_power_consumed_kilowatts gauge
+# UNIT dsmr_phase_power_consumed_kilowatts kilowatts
+# HELP dsmr_phase_power_consumed_kilowatts Actual power delivered to the client
+dsmr_phase_power_consumed_kilowatts{equipment_id="E0034567890123456",phase="l1"} 0.741
+# TYPE dsmr_power_generated_kilowatts gauge
+# UNIT dsmr_power_generated_kilowatts kilowatts
+# HELP dsmr_power_generated_kilowatts Actual power delivered by the client
+dsmr_power_generated_kilowatts{equipment_id="E0034567890123456"} 0
+# TYPE dsmr_phase_power_generated_kilowatts gauge
+# UNIT dsmr_phase_power_generated_kilowatts kilowatts
+# HELP dsmr_phase_power_generated_kilowatts Actual power delivered by the client
+dsmr_phase_power_generated_kilowatts{equipment_id="E0034567890123456",phase="l1"} 0
+# TYPE dsmr_current_amperes gauge
+# UNIT dsmr_current_amperes amperes
+# HELP dsmr_current_amperes Instantaneous current
+dsmr_current_amperes{equipment_id="E0034567890123456",phase="l1"} 4
+# TYPE dsmr_power_failures counter
+# HELP dsmr_power_failures Number of power failures
+dsmr_power_failures_total{equipment_id="E0034567890123456"} 2
+# TYPE dsmr_long_power_failures counter
+# HELP dsmr_long_power_failures Number of long power failures
+dsmr_long_power_failures_total{equipment_id="E0034567890123456"} 0
+# TYPE dsmr_voltage_sags counter
+# HELP dsmr_voltage_sags Number of voltage sags
+dsmr_voltage_sags_total{equipment_id="E0034567890123456",phase="l1"} 0
+# TYPE dsmr_voltage_swells counter
+# HELP dsmr_voltage_swells Number of voltage swells
+dsmr_voltage_swells_total{equipment_id="E0034567890123456",phase="l1"} 0
+# TYPE dsmr_mbus_reading counter
+# HELP dsmr_mbus_reading Last value captured from the M-Bus device
+dsmr_mbus_reading_total{channel="1",device_type="3",equipment_id="G0062749506872590",unit="m3"} 1619.203
+# EOF
+"#,
+		tgrm.to_openmetrics()
+	);
+}
+
+#[test]
+fn test_to_openmetrics_labels() {
+	let tgrm = Telegram {
+		ident: "ISK5\\2M550T-4567".to_string(),
+		electricity_equipment_id: Some("E\"1\"\n".to_string()),
+		mbus_devices: vec![MbusDevice {
+			capture_value: Some(UnitValue::without_unit(12.5)),
+			..MbusDevice::new(2)
+		}],
+		..Telegram::default()
+	};
+	assert_eq!(
+		r#"# TYPE dsmr_meter info
+# HELP dsmr_meter Meter identification
+dsmr_meter_info{equipment_id="E\"1\"\n",ident="ISK5\\2M550T-4567",version=""} 1
+# TYPE dsmr_mbus_reading counter
+# HELP dsmr_mbus_reading Last value captured from the M-Bus device
+dsmr_mbus_reading_total{channel="2",device_type="",equipment_id="",unit=""} 12.5
+# EOF
+"#,
+		tgrm.to_openmetrics()
+	);
+}
+
+#[test]
+fn test_to_openmetrics_units() {
+	let tgrm = Telegram {
+		electricity_consumed_tariff_1: Some(UnitValue::with_unit(5.0, "Wh")),
+		electricity_consumed_tariff_2: Some(UnitValue::with_unit(12.5, "m3")),
+		power: Some(UnitValue::with_unit(1500.0, "W")),
+		voltage_l1: Some(UnitValue::without_unit(230.1)),
+		..Telegram::default()
+	};
+	let metrics = tgrm.to_openmetrics();
+	assert!(metrics.contains("\ndsmr_electricity_consumed_kilowatt_hours_total{tariff=\"1\"} 0.005\n"));
+	assert!(!metrics.contains("tariff=\"2\""));
+	assert!(metrics.contains("\ndsmr_power_consumed_kilowatts 1.5\n"));
+	assert!(metrics.contains("\ndsmr_voltage_volts{phase=\"l1\"} 230.1\n"));
+}
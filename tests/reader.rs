@@ -0,0 +1,106 @@
+#![cfg(feature = "std")]
+
+use dsmr_parse::{Error, MAX_LINE_LEN, Telegram, TelegramReader};
+use matches::assert_matches;
+
+mod common;
+
+#[test]
+fn test_reader() {
+	let message = [
+		include_bytes!("telegram.txt").as_slice(),
+		include_bytes!("telegram2.txt"),
+		include_bytes!("telegram3.txt"),
+	]
+	.concat();
+	let idents = TelegramReader::new(message.as_slice())
+		.map(|tgrm| tgrm.unwrap().ident)
+		.collect::<Vec<_>>();
+	assert_eq!(vec!["XMX5LGBBFG1098765432", "ISK5\\2M550T-4567", "ISk5\\2MT382-1000"], idents);
+}
+
+#[test]
+fn test_reader_resync() {
+	let corrupted = common::corrupted_telegram();
+	let truncated = &include_bytes!("telegram2.txt")[..200];
+	let message = [
+		b"Garbage\r\n".as_slice(),
+		corrupted.as_bytes(),
+		truncated,
+		b"\r\n",
+		include_bytes!("telegram3.txt"),
+	]
+	.concat();
+	let mut reader = TelegramReader::new(message.as_slice());
+	assert_matches!(reader.next(), Some(Err(Error::CrcMismatch(..))));
+	assert_matches!(reader.next(), Some(Err(Error::TruncatedTelegram { lines_read: 9 })));
+	assert_matches!(reader.next(), Some(Ok(tgrm)) if tgrm.ident == "ISk5\\2MT382-1000");
+	assert_matches!(reader.next(), None);
+}
+
+#[test]
+fn test_read_from_keeps_rest() {
+	let message = [include_bytes!("telegram.txt").as_slice(), include_bytes!("telegram2.txt")].concat();
+	let mut src = message.as_slice();
+	let tgrm = Telegram::read_from(&mut src).unwrap().unwrap();
+	assert_eq!("XMX5LGBBFG1098765432", tgrm.ident);
+	assert_eq!(include_bytes!("telegram2.txt").as_slice(), src);
+	let tgrm = Telegram::read_from(&mut src).unwrap().unwrap();
+	assert_eq!("ISK5\\2M550T-4567", tgrm.ident);
+	assert!(src.is_empty());
+
+	let mut reader = TelegramReader::new(message.as_slice());
+	assert_matches!(reader.next(), Some(Ok(..)));
+	assert_eq!(include_bytes!("telegram2.txt").as_slice(), reader.into_inner());
+}
+
+#[test]
+fn test_reader_missing_header() {
+	let message = [
+		b"1-0:2.7.0(00.000*kW)\r\n!1234\r\n".as_slice(),
+		include_bytes!("telegram.txt"),
+		b"1-0:2.7.0(00.000*kW)\r\n!1234\r\n",
+	]
+	.concat();
+	let mut reader = TelegramReader::new(message.as_slice());
+	// the stream can start in the middle of a telegram
+	assert_matches!(reader.next(), Some(Ok(..)));
+	assert_matches!(reader.next(), Some(Err(Error::MissingHeader)));
+	assert_matches!(reader.next(), None);
+}
+
+#[test]
+fn test_reader_line_too_long() {
+	let message = [
+		b"/XMX5LGBBFG1009394887\r\n\r\n1-3:0.2.8(42)\r\n0-0:96.13.0(".as_slice(),
+		&[b'3'; 10000],
+		b")\r\n!0000\r\n",
+		include_bytes!("telegram.txt"),
+	]
+	.concat();
+	let mut reader = TelegramReader::new(message.as_slice());
+	assert_matches!(
+		reader.next(),
+		Some(Err(Error::Line(e))) if e.line_number == 4 && e.line.len() == MAX_LINE_LEN + 1 && matches!(e.error, Error::LineTooLong)
+	);
+	assert_matches!(reader.next(), Some(Ok(..)));
+	assert_matches!(reader.next(), None);
+}
+
+#[test]
+fn test_reader_skipped_bytes() {
+	let message = [
+		b"Garbage\r\n".as_slice(),
+		include_bytes!("telegram.txt"),
+		include_bytes!("telegram2.txt"),
+		&include_bytes!("telegram3.txt")[..200],
+	]
+	.concat();
+	let mut reader = TelegramReader::new(message.as_slice());
+	assert_matches!(reader.next(), Some(Ok(..)));
+	assert_eq!(9, reader.skipped_bytes());
+	assert_matches!(reader.next(), Some(Ok(..)));
+	assert_eq!(0, reader.skipped_bytes());
+	assert_matches!(reader.next(), Some(Err(Error::TruncatedTelegram { .. })));
+	assert_matches!(reader.next(), None);
+}
@@ -0,0 +1,61 @@
+#![cfg(all(feature = "serde", feature = "std"))]
+
+use dsmr_parse::{ObisCode, Telegram, Tst, UnitValue};
+use serde_json::json;
+
+mod common;
+
+#[test]
+fn test_serialize() {
+	let tgrm = Telegram::read_from(include_bytes!("telegram2.txt").as_slice())
+		.unwrap()
+		.unwrap();
+	let value = serde_json::to_value(&tgrm).unwrap();
+	assert_eq!(
+		json!({"datetime": "2023-03-09T19:10:49", "dst": false}),
+		value["electricity_date"]
+	);
+	assert_eq!(json!("E0087654210987654"), value["electricity_equipment_id"]);
+	assert_eq!(json!({"value": 233.6, "unit": "V"}), value["voltage_l2"]);
+	assert_eq!(json!("0-0:1.0.0"), value["objects"][1]["obis"]);
+	assert_eq!(json!("Gas"), value["mbus_devices"][0]["device_type"]);
+}
+
+#[test]
+fn test_round_trip() {
+	for message in common::TELEGRAMS {
+		let tgrm = Telegram::read_from(message).unwrap().unwrap();
+		let json = serde_json::to_string(&tgrm).unwrap();
+		assert_eq!(tgrm, serde_json::from_str(&json).unwrap());
+	}
+}
+
+#[test]
+fn test_tst() {
+	let tst = Tst {
+		year: 2023,
+		month: 10,
+		day: 29,
+		hour: 2,
+		minute: 30,
+		second: 0,
+		dst: true,
+	};
+	let json = serde_json::to_string(&tst).unwrap();
+	assert_eq!(r#"{"datetime":"2023-10-29T02:30:00","dst":true}"#, json);
+	assert_eq!(tst, serde_json::from_str(&json).unwrap());
+	assert!(serde_json::from_str::<Tst>(r#"{"datetime":"2023-10-29 02:30","dst":true}"#).is_err());
+}
+
+#[test]
+fn test_unit_value() {
+	let value = UnitValue::with_unit(4169.415, "kWh");
+	let json = serde_json::to_string(&value).unwrap();
+	assert_eq!(r#"{"value":4169.415,"unit":"kWh"}"#, json);
+	assert_eq!(value, serde_json::from_str(&json).unwrap());
+	assert_eq!(
+		UnitValue::without_unit(5),
+		serde_json::from_str::<UnitValue<u32>>(r#"{"value":5,"unit":null}"#).unwrap()
+	);
+	assert_eq!(ObisCode::POWER, serde_json::from_str::<ObisCode>(r#""1-0:1.7.0""#).unwrap());
+}
@@ -1,6 +1,15 @@
-use dsmr_parse::{PowerFailureEntry, Telegram, Tst, UnitValue};
+#![cfg(feature = "std")]
+
+use std::{mem, str};
+
+use dsmr_parse::{
+	CrcMode, DemandPeak, Error, MaximumDemandEntry, MbusDevice, MbusDeviceType, ObisCode, ParseOptions, PowerFailureEntry,
+	Telegram, TelegramReader, TelegramRef, Tst, UnitValue,
+};
 use matches::assert_matches;
 
+mod common;
+
 #[test]
 fn test_read_from() {
 	let message = include_bytes!("telegram.txt");
@@ -48,20 +57,38 @@ fn test_read_from() {
 		return_power_l1: Some(UnitValue::with_unit(0.0, "kW")),
 		return_power_l2: None,
 		return_power_l3: None,
-		device_type: Some("003".to_string()),
-		gas_equipment_id: Some("G0062749506872590".to_string()),
-		gas_date: Some(Tst {
-			year: 2019,
-			month: 1,
-			day: 1,
-			hour: 12,
-			minute: 0,
-			second: 0,
-			dst: false,
-		}),
-		gas_consumed: Some(UnitValue::with_unit(1619.203, "m3")),
+		emucs_version: None,
+		current_average_demand: None,
+		maximum_demand_month: None,
+		maximum_demand_history: vec![],
+		breaker_state: None,
+		limiter_threshold: None,
+		fuse_supervision_threshold: None,
+		mbus_devices: vec![MbusDevice {
+			channel: 1,
+			device_type: Some(MbusDeviceType::Gas),
+			equipment_id: Some("G0062749506872590".to_string()),
+			valve_position: None,
+			capture_date: Some(Tst {
+				year: 2019,
+				month: 1,
+				day: 1,
+				hour: 12,
+				minute: 0,
+				second: 0,
+				dst: false,
+			}),
+			capture_value: Some(UnitValue::with_unit(1619.203, "m3")),
+		}],
+		objects: vec![],
+		field_errors: vec![],
+		crc_valid: true,
+		crc_actual: 0x3547,
+		crc_expected: Some(0x3547),
 	};
-	assert_eq!(tgrm.unwrap(), Some(tgrm_ref));
+	let mut tgrm = tgrm.unwrap().unwrap();
+	assert_eq!(23, mem::take(&mut tgrm.objects).len());
+	assert_eq!(tgrm, tgrm_ref);
 }
 
 #[test]
@@ -122,20 +149,38 @@ fn test_read_from2() {
 		return_power_l1: Some(UnitValue::with_unit(0.0, "kW")),
 		return_power_l2: Some(UnitValue::with_unit(0.0, "kW")),
 		return_power_l3: Some(UnitValue::with_unit(0.0, "kW")),
-		device_type: Some("003".to_string()),
-		gas_equipment_id: Some("".to_string()),
-		gas_date: Some(Tst {
-			year: 2023,
-			month: 3,
-			day: 9,
-			hour: 19,
-			minute: 10,
-			second: 1,
-			dst: false,
-		}),
-		gas_consumed: Some(UnitValue::with_unit(0.595, "m3")),
+		emucs_version: None,
+		current_average_demand: None,
+		maximum_demand_month: None,
+		maximum_demand_history: vec![],
+		breaker_state: None,
+		limiter_threshold: None,
+		fuse_supervision_threshold: None,
+		mbus_devices: vec![MbusDevice {
+			channel: 1,
+			device_type: Some(MbusDeviceType::Gas),
+			equipment_id: Some("".to_string()),
+			valve_position: None,
+			capture_date: Some(Tst {
+				year: 2023,
+				month: 3,
+				day: 9,
+				hour: 19,
+				minute: 10,
+				second: 1,
+				dst: false,
+			}),
+			capture_value: Some(UnitValue::with_unit(0.595, "m3")),
+		}],
+		objects: vec![],
+		field_errors: vec![],
+		crc_valid: true,
+		crc_actual: 0x75D4,
+		crc_expected: Some(0x75D4),
 	};
-	assert_eq!(tgrm.unwrap(), Some(tgrm_ref));
+	let mut tgrm = tgrm.unwrap().unwrap();
+	assert_eq!(35, mem::take(&mut tgrm.objects).len());
+	assert_eq!(tgrm, tgrm_ref);
 }
 
 #[test]
@@ -210,20 +255,38 @@ fn test_read_from3() {
 		return_power_l1: Some(UnitValue::with_unit(4.444, "kW")),
 		return_power_l2: Some(UnitValue::with_unit(5.555, "kW")),
 		return_power_l3: Some(UnitValue::with_unit(6.666, "kW")),
-		device_type: Some("003".to_string()),
-		gas_equipment_id: Some("2222ABCD123456789".to_string()),
-		gas_date: Some(Tst {
-			year: 2010,
-			month: 12,
-			day: 9,
-			hour: 11,
-			minute: 25,
-			second: 0,
-			dst: false,
-		}),
-		gas_consumed: Some(UnitValue::with_unit(12785.123, "m3")),
+		emucs_version: None,
+		current_average_demand: None,
+		maximum_demand_month: None,
+		maximum_demand_history: vec![],
+		breaker_state: None,
+		limiter_threshold: None,
+		fuse_supervision_threshold: None,
+		mbus_devices: vec![MbusDevice {
+			channel: 1,
+			device_type: Some(MbusDeviceType::Gas),
+			equipment_id: Some("2222ABCD123456789".to_string()),
+			valve_position: None,
+			capture_date: Some(Tst {
+				year: 2010,
+				month: 12,
+				day: 9,
+				hour: 11,
+				minute: 25,
+				second: 0,
+				dst: false,
+			}),
+			capture_value: Some(UnitValue::with_unit(12785.123, "m3")),
+		}],
+		objects: vec![],
+		field_errors: vec![],
+		crc_valid: true,
+		crc_actual: 0xE47C,
+		crc_expected: Some(0xE47C),
 	};
-	assert_eq!(tgrm.unwrap(), Some(tgrm_ref));
+	let mut tgrm = tgrm.unwrap().unwrap();
+	assert_eq!(35, mem::take(&mut tgrm.objects).len());
+	assert_eq!(tgrm, tgrm_ref);
 }
 
 #[test]
@@ -234,3 +297,558 @@ fn test_empty() {
 	let tgrm = Telegram::read_from(b"Garbage" as &[u8]);
 	assert_matches!(tgrm, Ok(None));
 }
+
+#[test]
+fn test_truncated() {
+	let truncated = &include_bytes!("telegram.txt")[..200];
+	let tgrm = Telegram::read_from(truncated);
+	assert_matches!(tgrm, Err(Error::TruncatedTelegram { lines_read: 8 }));
+
+	let tgrm = Telegram::read_from(b"/XMX5LGBBFG1009394887\r\n" as &[u8]);
+	assert_matches!(tgrm, Err(Error::TruncatedTelegram { lines_read: 1 }));
+}
+
+#[test]
+fn test_read_from_v22() {
+	let message = include_bytes!("telegram_v22.txt");
+	let tgrm = Telegram::read_from(message.as_slice());
+	assert_matches!(tgrm, Ok(Some(..)));
+	let tgrm_ref = Telegram {
+		ident: "ISk5\\2ME382-1003".to_string(),
+		version: None,
+		electricity_date: None,
+		electricity_equipment_id: Some("ZBEV005106513513".to_string()),
+		electricity_consumed_tariff_1: Some(UnitValue::with_unit(608., "kWh")),
+		electricity_consumed_tariff_2: Some(UnitValue::with_unit(592., "kWh")),
+		electricity_generated_tariff_1: Some(UnitValue::with_unit(0., "kWh")),
+		electricity_generated_tariff_2: Some(UnitValue::with_unit(0., "kWh")),
+		current_tariff: Some("\0\u{1}".to_string()),
+		power: Some(UnitValue::with_unit(0.56, "kW")),
+		return_power: Some(UnitValue::with_unit(0., "kW")),
+		power_failure_count: None,
+		long_power_failure_count: None,
+		power_failure_log: vec![],
+		voltage_sag_l1_count: None,
+		voltage_sag_l2_count: None,
+		voltage_sag_l3_count: None,
+		voltage_swell_l1_count: None,
+		voltage_swell_l2_count: None,
+		voltage_swell_l3_count: None,
+		message: Some("".to_string()),
+		voltage_l1: None,
+		voltage_l2: None,
+		voltage_l3: None,
+		current_l1: None,
+		current_l2: None,
+		current_l3: None,
+		power_l1: None,
+		power_l2: None,
+		power_l3: None,
+		return_power_l1: None,
+		return_power_l2: None,
+		return_power_l3: None,
+		emucs_version: None,
+		current_average_demand: None,
+		maximum_demand_month: None,
+		maximum_demand_history: vec![],
+		breaker_state: Some(1),
+		limiter_threshold: Some(UnitValue::with_unit(999., "kW")),
+		fuse_supervision_threshold: None,
+		mbus_devices: vec![MbusDevice {
+			channel: 1,
+			device_type: Some(MbusDeviceType::Gas),
+			equipment_id: Some("28101E61708814011".to_string()),
+			valve_position: Some(1),
+			capture_date: Some(Tst {
+				year: 2012,
+				month: 10,
+				day: 30,
+				hour: 14,
+				minute: 0,
+				second: 0,
+				dst: false,
+			}),
+			capture_value: Some(UnitValue::with_unit(924.843, "m3")),
+		}],
+		objects: vec![],
+		field_errors: vec![],
+		crc_valid: true,
+		crc_actual: 0x3BB4,
+		crc_expected: None,
+	};
+	let mut tgrm = tgrm.unwrap().unwrap();
+	assert_eq!(16, mem::take(&mut tgrm.objects).len());
+	assert_eq!(tgrm, tgrm_ref);
+}
+
+#[test]
+fn test_read_from_be() {
+	let message = include_bytes!("telegram_be.txt");
+	let tgrm = Telegram::read_from(message.as_slice());
+	assert_matches!(tgrm, Ok(Some(..)));
+	let tgrm_ref = Telegram {
+		ident: "FLU5\\253769484_A".to_string(),
+		version: None,
+		electricity_date: Some(Tst {
+			year: 2020,
+			month: 5,
+			day: 12,
+			hour: 13,
+			minute: 54,
+			second: 9,
+			dst: true,
+		}),
+		electricity_equipment_id: Some("1SAG3101021605".to_string()),
+		electricity_consumed_tariff_1: Some(UnitValue::with_unit(0.034, "kWh")),
+		electricity_consumed_tariff_2: Some(UnitValue::with_unit(15.758, "kWh")),
+		electricity_generated_tariff_1: Some(UnitValue::with_unit(0., "kWh")),
+		electricity_generated_tariff_2: Some(UnitValue::with_unit(0.011, "kWh")),
+		current_tariff: Some("\0\u{1}".to_string()),
+		power: Some(UnitValue::with_unit(0., "kW")),
+		return_power: Some(UnitValue::with_unit(0., "kW")),
+		power_failure_count: None,
+		long_power_failure_count: None,
+		power_failure_log: vec![],
+		voltage_sag_l1_count: None,
+		voltage_sag_l2_count: None,
+		voltage_sag_l3_count: None,
+		voltage_swell_l1_count: None,
+		voltage_swell_l2_count: None,
+		voltage_swell_l3_count: None,
+		message: Some("".to_string()),
+		voltage_l1: Some(UnitValue::with_unit(234.7, "V")),
+		voltage_l2: Some(UnitValue::with_unit(234.7, "V")),
+		voltage_l3: Some(UnitValue::with_unit(234.7, "V")),
+		current_l1: Some(UnitValue::with_unit(0, "A")),
+		current_l2: Some(UnitValue::with_unit(0, "A")),
+		current_l3: Some(UnitValue::with_unit(0, "A")),
+		power_l1: Some(UnitValue::with_unit(0., "kW")),
+		power_l2: Some(UnitValue::with_unit(0., "kW")),
+		power_l3: Some(UnitValue::with_unit(0., "kW")),
+		return_power_l1: Some(UnitValue::with_unit(0., "kW")),
+		return_power_l2: Some(UnitValue::with_unit(0., "kW")),
+		return_power_l3: Some(UnitValue::with_unit(0., "kW")),
+		emucs_version: Some("50217".to_string()),
+		current_average_demand: Some(UnitValue::with_unit(2.351, "kW")),
+		maximum_demand_month: Some(DemandPeak {
+			date: Tst {
+				year: 2020,
+				month: 5,
+				day: 9,
+				hour: 13,
+				minute: 45,
+				second: 58,
+				dst: true,
+			},
+			demand: UnitValue::with_unit(2.589, "kW"),
+		}),
+		maximum_demand_history: vec![
+			MaximumDemandEntry {
+				month: Tst {
+					year: 2020,
+					month: 5,
+					day: 1,
+					hour: 0,
+					minute: 0,
+					second: 0,
+					dst: true,
+				},
+				date: Tst {
+					year: 2020,
+					month: 4,
+					day: 23,
+					hour: 19,
+					minute: 25,
+					second: 38,
+					dst: true,
+				},
+				demand: UnitValue::with_unit(3.695, "kW"),
+			},
+			MaximumDemandEntry {
+				month: Tst {
+					year: 2020,
+					month: 4,
+					day: 1,
+					hour: 0,
+					minute: 0,
+					second: 0,
+					dst: true,
+				},
+				date: Tst {
+					year: 2020,
+					month: 3,
+					day: 5,
+					hour: 12,
+					minute: 21,
+					second: 39,
+					dst: true,
+				},
+				demand: UnitValue::with_unit(5.98, "kW"),
+			},
+			MaximumDemandEntry {
+				month: Tst {
+					year: 2020,
+					month: 3,
+					day: 1,
+					hour: 0,
+					minute: 0,
+					second: 0,
+					dst: true,
+				},
+				date: Tst {
+					year: 2020,
+					month: 2,
+					day: 10,
+					hour: 3,
+					minute: 54,
+					second: 21,
+					dst: false,
+				},
+				demand: UnitValue::with_unit(4.318, "kW"),
+			},
+		],
+		breaker_state: Some(1),
+		limiter_threshold: Some(UnitValue::with_unit(999.9, "kW")),
+		fuse_supervision_threshold: Some(UnitValue::with_unit(999, "A")),
+		mbus_devices: vec![
+			MbusDevice {
+				channel: 1,
+				device_type: Some(MbusDeviceType::Gas),
+				equipment_id: Some("7FLO2119033733".to_string()),
+				valve_position: Some(1),
+				capture_date: Some(Tst {
+					year: 2020,
+					month: 5,
+					day: 12,
+					hour: 13,
+					minute: 45,
+					second: 58,
+					dst: true,
+				}),
+				capture_value: Some(UnitValue::with_unit(112.384, "m3")),
+			},
+			MbusDevice {
+				channel: 2,
+				device_type: Some(MbusDeviceType::Water),
+				equipment_id: Some("8SET0000000000".to_string()),
+				valve_position: None,
+				capture_date: Some(Tst {
+					year: 2020,
+					month: 5,
+					day: 12,
+					hour: 13,
+					minute: 45,
+					second: 0,
+					dst: true,
+				}),
+				capture_value: Some(UnitValue::with_unit(872.234, "m3")),
+			},
+		],
+		objects: vec![],
+		field_errors: vec![],
+		crc_valid: true,
+		crc_actual: 0x0922,
+		crc_expected: Some(0x0922),
+	};
+	let mut tgrm = tgrm.unwrap().unwrap();
+	assert_eq!(36, mem::take(&mut tgrm.objects).len());
+	assert_eq!(tgrm, tgrm_ref);
+}
+
+#[test]
+fn test_mbus_device_type() {
+	let tgrm = Telegram::read_from(include_bytes!("telegram_be.txt").as_slice())
+		.unwrap()
+		.unwrap();
+	assert_eq!(Some(1), tgrm.mbus_device_by_type(MbusDeviceType::Gas).map(|d| d.channel));
+	assert_eq!(Some(2), tgrm.mbus_device_by_type(MbusDeviceType::Water).map(|d| d.channel));
+	assert_eq!(None, tgrm.mbus_device_by_type(MbusDeviceType::Heat));
+
+	assert_eq!(MbusDeviceType::ColdWater, MbusDeviceType::from(0x16));
+	assert_eq!(MbusDeviceType::Other(0x30), MbusDeviceType::from(0x30));
+	assert_eq!(0x02, u8::from(MbusDeviceType::Electricity));
+}
+
+#[test]
+fn test_objects() {
+	let tgrm = Telegram::read_from(include_bytes!("telegram3.txt").as_slice())
+		.unwrap()
+		.unwrap();
+	assert_eq!(ObisCode::new(1, 3, 0, 2, 8, 255), tgrm.objects[0].obis);
+	assert_eq!(
+		["123456.789*kWh"],
+		tgrm.get(ObisCode::new(1, 0, 1, 8, 1, 255)).unwrap().values.as_slice()
+	);
+	assert_eq!(
+		[
+			"2",
+			"0-0:96.7.19",
+			"101208152415W",
+			"0000000240*s",
+			"101208151004W",
+			"0000000301*s"
+		],
+		tgrm.get(ObisCode::new(1, 0, 99, 97, 0, 255)).unwrap().values.as_slice()
+	);
+	assert_eq!(None, tgrm.get(ObisCode::new(1, 0, 1, 8, 3, 255)));
+	assert!(tgrm.objects.iter().all(|object| object.decoded));
+
+	let tgrm = Telegram::read_from(include_bytes!("telegram.txt").as_slice())
+		.unwrap()
+		.unwrap();
+	// not decoded into any field
+	let object = tgrm.get(ObisCode::new(0, 0, 96, 13, 1, 255)).unwrap();
+	assert_eq!([""], object.values.as_slice());
+	assert!(!object.decoded);
+	assert!(tgrm.get(ObisCode::MESSAGE).unwrap().decoded);
+}
+
+#[test]
+fn test_multi_value_object() {
+	let message = b"/XMX5LGBBFG1009394887\r\n\r\n1-3:0.2.8(50)\r\n0-0:96.99.1(1)(2.5*kW)()\r\n!EF55\r\n";
+	let tgrm = Telegram::read_from(message.as_slice()).unwrap().unwrap();
+	assert_eq!(
+		["1", "2.5*kW", ""],
+		tgrm.get(ObisCode::new(0, 0, 96, 99, 1, 255)).unwrap().values.as_slice()
+	);
+}
+
+#[test]
+fn test_missing_crc() {
+	let message = String::from_utf8(include_bytes!("telegram.txt").to_vec())
+		.unwrap()
+		.replace("!3547", "!");
+	let tgrm = Telegram::read_from(message.as_bytes());
+	assert_matches!(tgrm, Err(Error::InvalidInt(..)));
+
+	// e-MUCS telegrams don't report 0.2.8, but they aren't legacy either
+	let message = String::from_utf8(include_bytes!("telegram_be.txt").to_vec())
+		.unwrap()
+		.replace("!0922", "!");
+	assert_matches!(Telegram::read_from(message.as_bytes()), Err(Error::InvalidInt(..)));
+	assert_matches!(TelegramRef::parse(message.as_bytes()), Err(Error::InvalidInt(..)));
+
+	// neither are the telegrams with the DST flag in the timestamps
+	let message = "/XMX5LGBBFG1009394887\r\n\r\n0-0:1.0.0(190101125431W)\r\n1-0:1.8.1(004169.415*kWh)\r\n!\r\n";
+	assert_matches!(Telegram::read_from(message.as_bytes()), Err(Error::InvalidInt(..)));
+	let legacy = message.replace("190101125431W", "190101125431");
+	let tgrm = Telegram::read_from(legacy.as_bytes()).unwrap().unwrap();
+	assert!(tgrm.crc_valid);
+	assert_eq!(None, tgrm.crc_expected);
+}
+
+#[test]
+fn test_lenient() {
+	let message = "/XMX5LGBBFG1009394887\r\n\r\n1-3:0.2.8(50)\r\n1-0:1.8.1(004169.415*kWh)\r\n1-0:31.7.0(4.5*A)\r\n1-0:51.7.0(002*A)\r\n!8303\r\n";
+	assert_matches!(Telegram::read_from(message.as_bytes()), Err(Error::Line(e)) if matches!(e.error, Error::InvalidInt(..)));
+
+	let options = ParseOptions {
+		lenient: true,
+		..ParseOptions::default()
+	};
+	let tgrm = TelegramReader::with_options(message.as_bytes(), options)
+		.next()
+		.unwrap()
+		.unwrap();
+	assert_eq!(
+		Some(UnitValue::with_unit(4169.415, "kWh")),
+		tgrm.electricity_consumed_tariff_1
+	);
+	assert_eq!(None, tgrm.current_l1);
+	assert_eq!(Some(UnitValue::with_unit(2, "A")), tgrm.current_l2);
+	assert_eq!(1, tgrm.field_errors.len());
+	assert_eq!(ObisCode::CURRENT_L1, tgrm.field_errors[0].obis);
+	assert_eq!(["4.5*A"], tgrm.field_errors[0].values.as_slice());
+	assert!(tgrm.field_errors[0].message.starts_with("Invalid integer"));
+
+	// CRC is still checked
+	let corrupted = message.replace("004169.415", "004169.416");
+	let res = TelegramReader::with_options(corrupted.as_bytes(), options).next().unwrap();
+	assert_matches!(res, Err(Error::CrcMismatch(..)));
+
+	// malformed lines don't fail the telegram either
+	let junk = message.replace("1-0:51.7.0(002*A)", "XYZ garbage\r\n1-0:51.7.0(002*A)\r\n1-0:52.7.0(230.1*V");
+	let options = ParseOptions {
+		lenient: true,
+		crc: CrcMode::Ignore,
+	};
+	let tgrm = TelegramReader::with_options(junk.as_bytes(), options)
+		.next()
+		.unwrap()
+		.unwrap();
+	assert_eq!(Some(UnitValue::with_unit(2, "A")), tgrm.current_l2);
+	assert_eq!(2, tgrm.field_errors.len());
+	assert_eq!(ObisCode::VOLTAGE_L2, tgrm.field_errors[1].obis);
+	assert_eq!(["(230.1*V"], tgrm.field_errors[1].values.as_slice());
+}
+
+#[test]
+fn test_line_error() {
+	let message = "/XMX5LGBBFG1009394887\r\n\r\n1-3:0.2.8(50)\r\n1-0:1.8.1(004169.415*kWh)\r\n1-0:31.7.0(4.5*A)\r\n1-0:51.7.0(002*A)\r\n!8303\r\n";
+	let Err(Error::Line(e)) = Telegram::read_from(message.as_bytes()) else {
+		panic!("Line error expected");
+	};
+	assert_eq!(Some(ObisCode::CURRENT_L1), e.obis);
+	assert_eq!("1-0:31.7.0(4.5*A)", e.line);
+	assert_eq!(5, e.line_number);
+	assert_eq!(67, e.offset);
+	assert!(message[e.offset..].starts_with(&e.line));
+	assert_matches!(e.error, Error::InvalidInt(..));
+}
+
+#[test]
+fn test_invalid_lines() {
+	// the lines that aren't valid objects are skipped, only the undecodable values of the known objects are fatal
+	let message = "/XMX5LGBBFG1009394887\r\n\r\n1-3:0.2.8(50)\r\n1-0:1.8.1(004169.415*kWh)\r\nvendor junk\r\n1-0:1.8(0)\r\n1-0:31.7.0(004*A)\r\n!0AB0\r\n";
+	let tgrm = Telegram::read_from(message.as_bytes()).unwrap().unwrap();
+	assert_eq!(
+		Some(UnitValue::with_unit(4169.415, "kWh")),
+		tgrm.electricity_consumed_tariff_1
+	);
+	assert_eq!(Some(UnitValue::with_unit(4, "A")), tgrm.current_l1);
+	assert_eq!(3, tgrm.objects.len());
+	let tgrm_ref = TelegramRef::parse(message.as_bytes()).unwrap().unwrap();
+	assert_eq!(3, tgrm_ref.objects.len());
+
+	let malformed = "/XMX5LGBBFG1009394887\r\n\r\n1-3:0.2.8(50)\r\n1-0:1.8.1(004169.415*kWh)\r\n1-0:1.8.2(004884.452*kWh\r\n1-0:31.7.0(004*A)\r\n!AA15\r\n";
+	let tgrm = Telegram::read_from(malformed.as_bytes()).unwrap().unwrap();
+	assert_eq!(None, tgrm.electricity_consumed_tariff_2);
+	assert_eq!(Some(UnitValue::with_unit(4, "A")), tgrm.current_l1);
+	assert!(tgrm.field_errors.is_empty());
+}
+
+#[test]
+fn test_crc_mode() {
+	let corrupted = common::corrupted_telegram();
+	let read = |message: &str, crc| {
+		let options = ParseOptions {
+			crc,
+			..ParseOptions::default()
+		};
+		TelegramReader::with_options(message.as_bytes(), options).next().unwrap()
+	};
+	assert_matches!(read(&corrupted, CrcMode::Strict), Err(Error::CrcMismatch(..)));
+	for mode in [CrcMode::Accept, CrcMode::Ignore] {
+		let tgrm = read(&corrupted, mode).unwrap();
+		assert!(!tgrm.crc_valid);
+		assert_eq!(Some(0x3547), tgrm.crc_expected);
+		assert_ne!(0x3547, tgrm.crc_actual);
+		assert_eq!(
+			Some(UnitValue::with_unit(4169.416, "kWh")),
+			tgrm.electricity_consumed_tariff_1
+		);
+	}
+
+	let garbled = corrupted.replace("!3547", "!35?7");
+	assert_matches!(read(&garbled, CrcMode::Strict), Err(Error::InvalidInt(..)));
+	let tgrm = read(&garbled, CrcMode::Ignore).unwrap();
+	assert!(!tgrm.crc_valid);
+	assert_eq!(None, tgrm.crc_expected);
+
+	let tgrm = read(str::from_utf8(include_bytes!("telegram.txt")).unwrap(), CrcMode::Accept).unwrap();
+	assert!(tgrm.crc_valid);
+	assert_eq!(Some(tgrm.crc_actual), tgrm.crc_expected);
+}
+
+#[test]
+fn test_write_round_trip() {
+	for message in common::TELEGRAMS {
+		let tgrm = Telegram::read_from(message).unwrap().unwrap();
+		let written = tgrm.to_string();
+		let tgrm2 = Telegram::read_from(written.as_bytes()).unwrap().unwrap();
+		assert!(tgrm2.crc_valid);
+		assert_eq!(tgrm.objects.len(), tgrm2.objects.len());
+		assert!(tgrm.fields_eq(&tgrm2));
+		let mut buf = Vec::new();
+		tgrm2.write_to(&mut buf).unwrap();
+		assert_eq!(written.as_bytes(), buf);
+		assert_eq!(tgrm2, Telegram::read_from(buf.as_slice()).unwrap().unwrap());
+	}
+}
+
+#[test]
+fn test_write_field_errors() {
+	let message = "/XMX5LGBBFG1009394887\r\n\r\n1-3:0.2.8(50)\r\n1-0:31.7.0(4.5*A)\r\n0-0:96.99.9(1)\r\n!1F44\r\n";
+	let options = ParseOptions {
+		lenient: true,
+		crc: CrcMode::Ignore,
+	};
+	let tgrm = TelegramReader::with_options(message.as_bytes(), options)
+		.next()
+		.unwrap()
+		.unwrap();
+	let written = tgrm.to_string();
+	// the object that failed to decode is left out, the unknown one is written once
+	assert!(!written.contains("1-0:31.7.0"));
+	assert_eq!(1, written.matches("0-0:96.99.9(1)").count());
+	let parsed = Telegram::read_from(written.as_bytes()).unwrap().unwrap();
+	assert_eq!(None, parsed.current_l1);
+	assert!(tgrm.fields_eq(&parsed));
+}
+
+#[test]
+fn test_write_fields() {
+	let tgrm = Telegram {
+		ident: "ISK5\\2M550T-4567".to_string(),
+		version: Some("50".to_string()),
+		electricity_date: Some(Tst {
+			year: 2023,
+			month: 7,
+			day: 1,
+			hour: 9,
+			minute: 5,
+			second: 0,
+			dst: true,
+		}),
+		electricity_equipment_id: Some("E0087654210987654".to_string()),
+		current_tariff: Some("0002".to_string()),
+		power: Some(UnitValue::with_unit(0.25, "kW")),
+		power_failure_count: Some(3),
+		power_failure_log: vec![PowerFailureEntry {
+			end_date: Tst {
+				year: 2022,
+				month: 12,
+				day: 24,
+				hour: 18,
+				minute: 0,
+				second: 5,
+				dst: false,
+			},
+			duration: UnitValue::with_unit(240, "s"),
+		}],
+		message: Some("Hello".to_string()),
+		voltage_l1: Some(UnitValue::with_unit(230.1, "V")),
+		current_l1: Some(UnitValue::with_unit(2, "A")),
+		mbus_devices: vec![MbusDevice {
+			channel: 1,
+			device_type: Some(MbusDeviceType::Gas),
+			capture_date: None,
+			capture_value: Some(UnitValue::with_unit(12.5, "m3")),
+			..MbusDevice::default()
+		}],
+		crc_valid: true,
+		..Telegram::default()
+	};
+	let written = tgrm.to_string();
+	assert_eq!(
+		concat!(
+			"/ISK5\\2M550T-4567\r\n\r\n",
+			"1-3:0.2.8(50)\r\n",
+			"0-0:1.0.0(230701090500S)\r\n",
+			"0-0:96.1.1(4530303837363534323130393837363534)\r\n",
+			"0-0:96.14.0(30303032)\r\n",
+			"1-0:1.7.0(00.250*kW)\r\n",
+			"0-0:96.7.21(00003)\r\n",
+			"1-0:99.97.0(1)(0-0:96.7.19)(221224180005W)(0000000240*s)\r\n",
+			"0-0:96.13.0(48656C6C6F)\r\n",
+			"1-0:32.7.0(230.1*V)\r\n",
+			"1-0:31.7.0(002*A)\r\n",
+			"0-1:24.1.0(003)\r\n",
+			"0-1:24.2.1()(00012.500*m3)\r\n",
+		),
+		&written[..written.len() - 7]
+	);
+	let parsed = Telegram::read_from(written.as_bytes()).unwrap().unwrap();
+	assert!(tgrm.fields_eq(&parsed));
+	assert!(!tgrm.fields_eq(&Telegram::default()));
+}
@@ -0,0 +1,118 @@
+use dsmr_parse::{CrcMode, Error, ObisCode, ParseOptions, TelegramDecoder, TelegramRef, Tst, UnitValue};
+use matches::assert_matches;
+
+mod common;
+
+#[test]
+fn test_parse() {
+	let message = include_bytes!("telegram2.txt");
+	let tgrm = TelegramRef::parse(message).unwrap().unwrap();
+	assert_eq!("ISK5\\2M550T-4567", tgrm.ident);
+	assert_eq!(35, tgrm.objects.len());
+	let voltage = tgrm.get(ObisCode::VOLTAGE_L2).unwrap();
+	assert_eq!("1-0:52.7.0", voltage.obis.to_string());
+	assert_eq!("(233.6*V)", voltage.raw_values);
+	assert_eq!(vec!["233.6*V"], voltage.values().collect::<Vec<_>>());
+	assert_eq!(
+		Ok(UnitValue {
+			value: 233.6,
+			unit: Some("V")
+		}),
+		voltage.unit_value::<f64>()
+	);
+	assert_eq!(
+		Some("E0087654210987654".to_string()),
+		tgrm.get(ObisCode::ELECTRICITY_EQUIPMENT_ID).unwrap().octet_string()
+	);
+	assert_eq!(
+		Some(Tst {
+			year: 2023,
+			month: 3,
+			day: 9,
+			hour: 19,
+			minute: 10,
+			second: 49,
+			dst: false,
+		}),
+		tgrm.get(ObisCode::ELECTRICITY_DATE).unwrap().tst()
+	);
+
+	let decoded = tgrm.to_telegram().unwrap();
+	assert_eq!(decoded.version.as_deref(), tgrm.version());
+	assert_eq!(decoded.electricity_date, tgrm.electricity_date());
+	assert_eq!(
+		Some(UnitValue {
+			value: 233.6,
+			unit: Some("V")
+		}),
+		tgrm.voltage_l2()
+	);
+	assert_eq!(decoded.current_l1, tgrm.current_l1().map(UnitValue::into_owned));
+	assert_eq!(
+		decoded.electricity_consumed_tariff_2,
+		tgrm.electricity_consumed_tariff_2().map(UnitValue::into_owned)
+	);
+	assert_eq!(decoded.return_power_l3, tgrm.return_power_l3().map(UnitValue::into_owned));
+}
+
+#[test]
+fn test_to_telegram() {
+	for message in common::TELEGRAMS {
+		let tgrm = TelegramRef::parse(message).unwrap().unwrap().to_telegram().unwrap();
+		let mut decoder = TelegramDecoder::new();
+		let tgrm_ref = decoder.feed(message).pop().unwrap().unwrap();
+		assert_eq!(tgrm_ref, tgrm);
+	}
+}
+
+#[test]
+fn test_parse_invalid() {
+	assert_matches!(TelegramRef::parse(b""), Ok(None));
+	assert_matches!(TelegramRef::parse(b"Garbage"), Ok(None));
+	let truncated = &include_bytes!("telegram.txt")[..200];
+	assert_matches!(TelegramRef::parse(truncated), Ok(None));
+	let corrupted = common::corrupted_telegram();
+	assert_matches!(TelegramRef::parse(corrupted.as_bytes()), Err(Error::CrcMismatch(..)));
+	let tgrm = TelegramRef::parse_with_crc_mode(corrupted.as_bytes(), CrcMode::Accept)
+		.unwrap()
+		.unwrap();
+	assert!(!tgrm.crc_valid);
+	assert_eq!(Some(0x3547), tgrm.crc_expected);
+}
+
+#[test]
+fn test_parse_legacy() {
+	let tgrm = TelegramRef::parse(include_bytes!("telegram_v22.txt")).unwrap().unwrap();
+	let gas = tgrm.get("0-1:24.3.0".parse().unwrap()).unwrap();
+	assert_eq!(
+		vec!["121030140000", "00", "60", "1", "0-1:24.2.1", "m3", "00924.843"],
+		gas.values().collect::<Vec<_>>()
+	);
+	let tgrm = tgrm.to_telegram().unwrap();
+	assert_eq!(
+		Some(UnitValue::with_unit(924.843, "m3")),
+		tgrm.mbus_device(1).unwrap().capture_value
+	);
+}
+
+#[test]
+fn test_parse_malformed_line() {
+	let message = String::from_utf8(include_bytes!("telegram.txt").to_vec())
+		.unwrap()
+		.replace("004884.452*kWh)", "004884.452*kWh");
+	let options = ParseOptions {
+		crc: CrcMode::Ignore,
+		..ParseOptions::default()
+	};
+	let telegram = TelegramRef::parse_with_crc_mode(message.as_bytes(), CrcMode::Ignore)
+		.unwrap()
+		.unwrap();
+	assert_eq!(None, telegram.get(ObisCode::ELECTRICITY_CONSUMED_TARIFF_2));
+	assert!(telegram.get(ObisCode::ELECTRICITY_CONSUMED_TARIFF_1).is_some());
+	let decoded = TelegramDecoder::with_options(options)
+		.feed(message.as_bytes())
+		.pop()
+		.unwrap()
+		.unwrap();
+	assert_eq!(decoded, telegram.to_telegram().unwrap());
+}